@@ -0,0 +1,53 @@
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+use crate::drill::partly_dealt_shoe;
+use crate::game::quiz::QuizScore;
+use crate::game::shoe::{Shoe, ACES_PER_DECK};
+use crate::input::get_user_number;
+
+/// Cards are flashed a round's worth at a time.
+const CARDS_PER_FLASH : usize = 6;
+
+/// At most this share of the shoe is flashed before the question.
+const MAX_PENETRATION_PERCENTAGE : usize = 75;
+
+/// Flashes a random part of a fresh shoe and asks how many aces are left
+/// in it, `rounds` times.
+pub fn run(num_of_decks : u8, rounds : u32, delay : Duration) -> QuizScore {
+    let mut score: QuizScore = QuizScore::default();
+
+    for _round in 0..rounds {
+        let shoe: Shoe = match partly_dealt_shoe(num_of_decks, MAX_PENETRATION_PERCENTAGE) {
+            Some(shoe) => shoe,
+            None => {
+                println!("A shoe needs at least one deck");
+                return score;
+            },
+        };
+        println!("{} deck shoe, {} aces to start with", num_of_decks, num_of_decks as u16 * ACES_PER_DECK);
+        thread::sleep(delay);
+
+        for cards in shoe.seen_cards().chunks(CARDS_PER_FLASH) {
+            let flash: Vec<String> = cards.iter().map(|card| card.to_string()).collect();
+            print!("\r{:<18}", flash.join(" "));
+            let _ = io::stdout().flush();
+            thread::sleep(delay);
+        }
+        println!("\r{:<18}", "");
+
+        let aces_remaining: u16 = shoe.aces_remaining();
+        let answer: Option<f64> = get_user_number("How many aces remain? ");
+        let correct: bool = answer == Some(aces_remaining as f64);
+        score.record(correct);
+        println!("{} {} aces remain after {} cards, {:+.1} against a normal shoe",
+                 if correct { "CORRECT!" } else { "WRONG!" },
+                 aces_remaining,
+                 shoe.cards_dealt(),
+                 shoe.ace_surplus());
+    }
+
+    println!("Ace counts: {}/{} correct ({:.0}%)", score.correct, score.asked, score.accuracy() * 100.0);
+    return score;
+}
@@ -0,0 +1,188 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::{thread_rng, Rng};
+
+use crate::game::count::CountingSystem;
+use crate::game::quiz;
+use crate::game::shoe::Shoe;
+use crate::input::get_user_number;
+
+pub const HISTORY_FILE : &str = "countdown_history.txt";
+
+/// Up to this many cards are burned unseen, so a full countdown doesn't
+/// always finish on the same count.
+const MAX_BURN_CARDS : u32 = 5;
+const SESSIONS_SHOWN : usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountdownSettings {
+    pub num_of_decks : u8,
+    pub group_size   : usize,
+    pub delay        : Duration,
+}
+
+impl Default for CountdownSettings {
+    fn default() -> CountdownSettings {
+        CountdownSettings {
+            num_of_decks : 1,
+            group_size : 1,
+            delay : Duration::from_millis(1000),
+        }
+    }
+}
+
+/// One run through the cards, as kept in the history file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CountdownResult {
+    pub num_of_cards   : usize,
+    pub group_size     : usize,
+    pub delay_ms       : u64,
+    pub answer_seconds : f64,
+    pub correct        : bool,
+}
+
+impl CountdownResult {
+    pub fn to_line(self) -> String {
+        return format!("{} {} {} {:.2} {}", self.num_of_cards, self.group_size, self.delay_ms, self.answer_seconds, self.correct as u8);
+    }
+
+    pub fn from_line(line : &str) -> Option<CountdownResult> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 5 {
+            return None;
+        }
+        return Some(CountdownResult {
+            num_of_cards : fields[0].parse().ok()?,
+            group_size : fields[1].parse().ok()?,
+            delay_ms : fields[2].parse().ok()?,
+            answer_seconds : fields[3].parse().ok()?,
+            correct : fields[4] == "1",
+        });
+    }
+}
+
+/// Results of earlier sessions, oldest first. A missing file is an empty history.
+pub fn load_history(path : &str) -> Vec<CountdownResult> {
+    let text: String = fs::read_to_string(path).unwrap_or_default();
+    return text.lines().filter_map(CountdownResult::from_line).collect();
+}
+
+pub fn save_result(path : &str, result : &CountdownResult) -> io::Result<()> {
+    let mut file: fs::File = OpenOptions::new().create(true).append(true).open(path)?;
+    return writeln!(file, "{}", result.to_line());
+}
+
+/// Shuffles `settings.num_of_decks` decks, shows them `settings.group_size`
+/// cards at a time and asks for the running count at the end, or `None`
+/// when there are no decks or no cards in a group.
+pub fn run(settings : &CountdownSettings, system : CountingSystem) -> Option<CountdownResult> {
+    if settings.num_of_decks == 0 {
+        println!("A shoe needs at least one deck");
+        return None;
+    }
+    if settings.group_size == 0 {
+        println!("A group needs at least one card");
+        return None;
+    }
+
+    let mut shoe: Shoe = Shoe::new(settings.num_of_decks, 100, Vec::new());
+    shoe.set_counting_system(system);
+    for _deck in 0..settings.num_of_decks {
+        shoe.add_deck();
+    }
+    shoe.shuffle();
+
+    let mut burned_cards: Vec<char> = Vec::new();
+    for _card in 0..thread_rng().gen_range(1..=MAX_BURN_CARDS) {
+        burned_cards.push(shoe.burn_card());
+    }
+    let num_of_cards: usize = shoe.cards_remaining();
+    println!("{} cards burned face down, counting the other {} with {}", burned_cards.len(), num_of_cards, shoe.counting_system().name());
+    thread::sleep(settings.delay);
+
+    while shoe.cards_remaining() > 0 {
+        let group: Vec<String> = (0..settings.group_size).filter_map(|_card| shoe.draw_card())
+                                                         .map(|card| card.to_string())
+                                                         .collect();
+        print!("\r{:<12}", group.join(" "));
+        let _ = io::stdout().flush();
+        thread::sleep(settings.delay);
+    }
+    println!("\r{:<12}", "");
+
+    let started: Instant = Instant::now();
+    let answer: Option<f64> = get_user_number("What is the running count? ");
+    let answer_seconds: f64 = started.elapsed().as_secs_f64();
+
+    let running_count: f64 = shoe.running_count();
+    let correct: bool = quiz::running_count_correct(answer.unwrap_or(f64::NAN), running_count);
+    if correct {
+        println!("CORRECT! Running count {:+}", running_count);
+    }
+    else {
+        println!("WRONG! Running count {:+}", running_count);
+    }
+    println!("Burned cards: {}", burned_cards.iter().map(|card| card.to_string()).collect::<Vec<String>>().join(" "));
+
+    return Some(CountdownResult {
+        num_of_cards,
+        group_size : settings.group_size,
+        delay_ms : settings.delay.as_millis() as u64,
+        answer_seconds,
+        correct,
+    });
+}
+
+/// Recent sessions, overall accuracy and the fastest speed counted correctly.
+pub fn display_trend(history : &[CountdownResult]) {
+    if history.is_empty() {
+        return;
+    }
+
+    println!("Last {} of {} sessions:", history.len().min(SESSIONS_SHOWN), history.len());
+    for result in history.iter().skip(history.len().saturating_sub(SESSIONS_SHOWN)) {
+        println!("  {} cards, {} at a time every {} ms, answered in {:.1}s, {}",
+                 result.num_of_cards,
+                 result.group_size,
+                 result.delay_ms,
+                 result.answer_seconds,
+                 if result.correct { "correct" } else { "wrong" });
+    }
+
+    let num_correct: usize = history.iter().filter(|result| result.correct).count();
+    println!("Accuracy: {}/{} ({:.0}%)", num_correct, history.len(), num_correct as f64 * 100.0 / history.len() as f64);
+    if let Some(fastest) = history.iter().filter(|result| result.correct).map(|result| result.delay_ms as f64 / result.group_size as f64).reduce(f64::min) {
+        println!("Fastest correct countdown: {:.0} ms per card", fastest);
+    }
+}
+
+
+#[cfg(test)]
+mod countdown_tests {
+    use super::*;
+
+    #[test]
+    fn countdown_result_round_trip() {
+        let result = CountdownResult {
+            num_of_cards : 49,
+            group_size : 2,
+            delay_ms : 400,
+            answer_seconds : 3.25,
+            correct : true,
+        };
+        assert_eq!(result.to_line(), "49 2 400 3.25 1");
+        assert_eq!(CountdownResult::from_line(&result.to_line()), Some(result));
+        assert_eq!(CountdownResult::from_line("49 2 fast 3.25 1"), None);
+    }
+
+    #[test]
+    fn countdown_needs_decks_and_groups() {
+        let no_decks = CountdownSettings { num_of_decks : 0, ..CountdownSettings::default() };
+        assert_eq!(run(&no_decks, CountingSystem::hi_lo()), None);
+        let no_group = CountdownSettings { group_size : 0, ..CountdownSettings::default() };
+        assert_eq!(run(&no_group, CountingSystem::hi_lo()), None);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::drill::countdown::{self, CountdownResult, CountdownSettings};
+use crate::drill::flash::{self, HandFilter};
+use crate::drill::{indexes, ramp, totals, true_count};
+use crate::game::betting::BetRamp;
+use crate::game::chart::Chart;
+use crate::game::count::CountingSystem;
+use crate::game::deviation::IndexTable;
+use crate::game::quiz::QuizScore;
+use crate::game::review::ReviewSchedule;
+use crate::game::rules::Rules;
+use crate::game::strategy::StrategyMode;
+
+/// Strategy lessons are drilled on a six deck game.
+const NUM_OF_DECKS : u8 = 6;
+
+/// The lessons in the order they are taken. Each one unlocks the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lesson {
+    HandTotals,
+    HardStrategy,
+    SoftStrategy,
+    Pairs,
+    Surrender,
+    Counting,
+    TrueCount,
+    Deviations,
+    BetRamp,
+}
+
+pub const LESSONS : [Lesson; 9] = [Lesson::HandTotals,
+                                   Lesson::HardStrategy,
+                                   Lesson::SoftStrategy,
+                                   Lesson::Pairs,
+                                   Lesson::Surrender,
+                                   Lesson::Counting,
+                                   Lesson::TrueCount,
+                                   Lesson::Deviations,
+                                   Lesson::BetRamp];
+
+impl Lesson {
+    pub fn name(&self) -> &str {
+        match self {
+            Lesson::HandTotals   => return "Hand totals",
+            Lesson::HardStrategy => return "Hard strategy",
+            Lesson::SoftStrategy => return "Soft strategy",
+            Lesson::Pairs        => return "Pairs",
+            Lesson::Surrender    => return "Surrender",
+            Lesson::Counting     => return "Counting",
+            Lesson::TrueCount    => return "True count",
+            Lesson::Deviations   => return "Deviations",
+            Lesson::BetRamp      => return "Bet ramp",
+        }
+    }
+
+    /// What the lesson drills, in a line.
+    pub fn description(&self) -> &str {
+        match self {
+            Lesson::HandTotals   => return "total hands of several cards and tell soft from hard",
+            Lesson::HardStrategy => return "play hard totals by the chart",
+            Lesson::SoftStrategy => return "play soft totals by the chart",
+            Lesson::Pairs        => return "split pairs by the chart",
+            Lesson::Surrender    => return "surrender the hands worth giving up",
+            Lesson::Counting     => return "keep the Hi-Lo running count through a deck",
+            Lesson::TrueCount    => return "divide the running count by the decks left in the shoe",
+            Lesson::Deviations   => return "make the Illustrious 18 and Fab 4 index plays",
+            Lesson::BetRamp      => return "bet the ramp for the true count",
+        }
+    }
+
+    /// Questions asked in one attempt.
+    pub fn rounds(&self) -> u32 {
+        match self {
+            Lesson::Counting => return 1,
+            Lesson::HandTotals | Lesson::Surrender | Lesson::TrueCount | Lesson::BetRamp => return 10,
+            _=> return 20,
+        }
+    }
+
+    /// Share of answers needed to pass.
+    pub fn pass_mark(&self) -> f64 {
+        match self {
+            Lesson::Counting   => return 1.0,
+            Lesson::Deviations => return 0.8,
+            _=> return 0.9,
+        }
+    }
+
+    /// An attempt passes if every question was answered and enough were right.
+    pub fn passed(&self, score : QuizScore) -> bool {
+        return score.asked >= self.rounds() && score.accuracy() >= self.pass_mark();
+    }
+
+    /// The name the lesson's answers are kept under in a profile's drill history.
+    pub fn drill(&self) -> &str {
+        match self {
+            Lesson::HandTotals   => return "totals",
+            Lesson::HardStrategy | Lesson::SoftStrategy | Lesson::Pairs | Lesson::Surrender => return "flash",
+            Lesson::Counting     => return "countdown",
+            Lesson::TrueCount    => return "true-count",
+            Lesson::Deviations   => return "indexes",
+            Lesson::BetRamp      => return "ramp",
+        }
+    }
+
+    /// The situations a strategy lesson deals, reviews included, or `None`
+    /// for lessons that aren't flash card drills.
+    pub fn hand_filter(&self) -> Option<HandFilter> {
+        match self {
+            Lesson::HardStrategy => return Some(HandFilter::Hard),
+            Lesson::SoftStrategy => return Some(HandFilter::Soft),
+            Lesson::Pairs        => return Some(HandFilter::Pairs),
+            Lesson::Surrender    => return Some(HandFilter::Surrender),
+            _=> return None,
+        }
+    }
+
+    /// Runs one attempt at the lesson. Strategy answers are recorded in `schedule`.
+    pub fn run(&self, chart : &Chart, rules : &Rules, schedule : &mut ReviewSchedule) -> QuizScore {
+        println!("Lesson: {}, {}. {} questions, {:.0}% to pass", self.name(), self.description(), self.rounds(), self.pass_mark() * 100.0);
+        match self {
+            Lesson::HandTotals   => return totals::run(self.rounds()),
+            Lesson::HardStrategy | Lesson::SoftStrategy | Lesson::Pairs | Lesson::Surrender => {
+                let filter: HandFilter = self.hand_filter().expect("strategy lessons have a hand filter");
+                return flash::run(filter, self.rounds(), NUM_OF_DECKS, StrategyMode::TotalDependent, chart, rules, schedule);
+            },
+            Lesson::Counting     => {
+                let result: Option<CountdownResult> = countdown::run(&CountdownSettings::default(), CountingSystem::hi_lo());
+                return result.map_or(QuizScore::default(), |result| QuizScore { asked : 1, correct : result.correct as u32 });
+            },
+            Lesson::TrueCount    => return true_count::run(NUM_OF_DECKS, self.rounds()),
+            Lesson::Deviations   => return indexes::run(self.rounds(), &IndexTable::illustrious_18_fab_4(), chart, rules),
+            Lesson::BetRamp      => return ramp::run(&BetRamp::default_ramp(), self.rounds()),
+        }
+    }
+}
+
+/// Lists the lessons with those passed ticked off and the next one marked.
+pub fn display(lessons_passed : usize) {
+    for (number, lesson) in LESSONS.iter().enumerate() {
+        let status: &str = if number < lessons_passed { "passed" } else if number == lessons_passed { "next" } else { "locked" };
+        println!("{}. {:<14} {:<7} {}", number + 1, lesson.name(), status, lesson.description());
+    }
+    if lessons_passed >= LESSONS.len() {
+        println!("Every lesson is passed");
+    }
+}
+
+
+#[cfg(test)]
+mod curriculum_tests {
+    use super::*;
+    use crate::game::chart::Section;
+
+    #[test]
+    fn passing_needs_every_question_and_the_mark() {
+        assert!(Lesson::HardStrategy.passed(QuizScore { asked : 20, correct : 18 }));
+        assert!(!Lesson::HardStrategy.passed(QuizScore { asked : 20, correct : 17 }));
+        assert!(!Lesson::HardStrategy.passed(QuizScore { asked : 5, correct : 5 }));
+        assert!(Lesson::Counting.passed(QuizScore { asked : 1, correct : 1 }));
+        assert!(!Lesson::Counting.passed(QuizScore { asked : 1, correct : 0 }));
+        assert!(Lesson::Deviations.passed(QuizScore { asked : 20, correct : 16 }));
+    }
+
+    #[test]
+    fn strategy_lessons_only_review_their_own_section() {
+        let chart = Chart::basic_strategy();
+        let rules = Rules::default();
+        let mut schedule = ReviewSchedule::new();
+        schedule.record(Section::Hard, 0, 0, &rules.code(), false, 100);
+
+        for (lesson, due) in [(Lesson::HardStrategy, 1), (Lesson::SoftStrategy, 0), (Lesson::Pairs, 0), (Lesson::Surrender, 0)] {
+            let filter = lesson.hand_filter().unwrap();
+            assert_eq!(flash::due_for_review(&schedule, filter, &chart, &rules, 101).len(), due);
+        }
+        assert_eq!(Lesson::Counting.hand_filter(), None);
+    }
+}
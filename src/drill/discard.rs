@@ -0,0 +1,99 @@
+use rand::{thread_rng, Rng};
+
+use crate::drill::partly_dealt_shoe;
+use crate::game::quiz::QuizScore;
+use crate::game::shoe::{Shoe, CARDS_PER_DECK};
+use crate::input::get_user_number;
+
+/// Each line of the drawn tray holds a quarter of a deck.
+const ROWS_PER_DECK : usize = 4;
+const TRAY_WIDTH    : usize = 12;
+
+/// The tray is never filled past this share of the shoe.
+const MAX_PENETRATION_PERCENTAGE : usize = 85;
+
+/// Draws a discard tray tall enough for a whole shoe of `num_of_decks`,
+/// filled to the height of `cards_dealt`.
+pub fn render_tray(cards_dealt : usize, num_of_decks : u8) -> String {
+    let num_of_rows: usize = num_of_decks as usize * ROWS_PER_DECK;
+    let cards_per_row: f64 = CARDS_PER_DECK as f64 / ROWS_PER_DECK as f64;
+    let filled_rows: usize = ((cards_dealt as f64 / cards_per_row).round() as usize).min(num_of_rows);
+
+    let mut tray: String = String::new();
+    for row in 0..num_of_rows {
+        let fill: &str = if row >= num_of_rows - filled_rows { "#" } else { " " };
+        tray += &format!("|{}|\n", fill.repeat(TRAY_WIDTH));
+    }
+    tray += &format!("+{}+\n", "-".repeat(TRAY_WIDTH));
+    return tray;
+}
+
+pub fn nearest_half_deck(decks : f64) -> f64 {
+    return (decks * 2.0).round() / 2.0;
+}
+
+/// An estimate is right if it is the half deck nearest the actual number.
+pub fn estimate_correct(answer : f64, decks : f64) -> bool {
+    return (answer - decks).abs() <= 0.25;
+}
+
+/// Deals a random part of a fresh shoe into the tray and asks how many
+/// decks have been played or are left, `rounds` times.
+pub fn run(num_of_decks : u8, rounds : u32) -> QuizScore {
+    let mut score: QuizScore = QuizScore::default();
+
+    for _round in 0..rounds {
+        let shoe: Shoe = match partly_dealt_shoe(num_of_decks, MAX_PENETRATION_PERCENTAGE) {
+            Some(shoe) => shoe,
+            None => {
+                println!("A shoe needs at least one deck");
+                return score;
+            },
+        };
+
+        println!("{} deck shoe, discard tray:", num_of_decks);
+        print!("{}", render_tray(shoe.cards_dealt(), num_of_decks));
+
+        let ask_played: bool = thread_rng().gen_bool(0.5);
+        let decks: f64 = if ask_played { shoe.cards_dealt() } else { shoe.cards_remaining() } as f64 / CARDS_PER_DECK as f64;
+        let answer: Option<f64> = get_user_number(if ask_played { "How many decks have been played? " } else { "How many decks are left? " });
+
+        let correct: bool = estimate_correct(answer.unwrap_or(f64::NAN), decks);
+        score.record(correct);
+        println!("{} {:.2} decks {} ({} cards), to the nearest half deck {:.1}",
+                 if correct { "CORRECT!" } else { "WRONG!" },
+                 decks,
+                 if ask_played { "played" } else { "left" },
+                 (decks * CARDS_PER_DECK as f64).round(),
+                 nearest_half_deck(decks));
+    }
+
+    println!("Estimates: {}/{} correct ({:.0}%)", score.correct, score.asked, score.accuracy() * 100.0);
+    return score;
+}
+
+
+#[cfg(test)]
+mod discard_tests {
+    use super::*;
+
+    #[test]
+    fn tray_height_follows_cards_dealt() {
+        let tray = render_tray(78, 2);
+        let rows: Vec<&str> = tray.lines().collect();
+        assert_eq!(rows.len(), 9);
+        assert_eq!(rows.iter().filter(|row| row.contains('#')).count(), 6);
+        assert_eq!(rows[1], "|            |");
+        assert_eq!(rows[2], "|############|");
+        assert_eq!(rows[8], "+------------+");
+    }
+
+    #[test]
+    fn estimates_graded_to_nearest_half_deck() {
+        assert_eq!(nearest_half_deck(2.8), 3.0);
+        assert_eq!(nearest_half_deck(2.7), 2.5);
+        assert!(estimate_correct(2.5, 2.7));
+        assert!(!estimate_correct(2.0, 2.7));
+        assert!(!estimate_correct(3.5, 2.7));
+    }
+}
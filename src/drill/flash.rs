@@ -0,0 +1,217 @@
+use rand::{thread_rng, Rng};
+
+use crate::game::action::UserAction;
+use crate::game::chart::{self, Chart, ChartAction, Section, UPCARDS};
+use crate::game::composition::rank_index;
+use crate::game::dealer::DealerOdds;
+use crate::game::explain;
+use crate::game::quiz::QuizScore;
+use crate::game::review::{self, ReviewItem, ReviewSchedule};
+use crate::game::rules::Rules;
+use crate::game::shoe::SUIT;
+use crate::game::strategy::{self, StrategyMode};
+use crate::input::{read_user_input, user_input_to_action};
+
+/// Random hands are drawn until one fits the filter, giving up after this many.
+const MAX_DRAWS : u32 = 10000;
+
+/// Which situations the drill deals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandFilter {
+    All,
+    Hard,
+    Soft,
+    Pairs,
+    Surrender,
+}
+
+impl HandFilter {
+    pub fn from_name(name : &str) -> Option<HandFilter> {
+        match name {
+            "all"       => Some(HandFilter::All),
+            "hard"      => Some(HandFilter::Hard),
+            "soft"      => Some(HandFilter::Soft),
+            "pairs"     => Some(HandFilter::Pairs),
+            "surrender" => Some(HandFilter::Surrender),
+            _=> None,
+        }
+    }
+
+    /// Whether a starting hand in chart cell `section`, `row` against the
+    /// upcard in `column` belongs in the drill.
+    pub fn matches(&self, chart : &Chart, section : Section, row : usize, column : usize) -> bool {
+        match self {
+            HandFilter::All       => return true,
+            HandFilter::Hard      => return section == Section::Hard,
+            HandFilter::Soft      => return section == Section::Soft,
+            HandFilter::Pairs     => return section == Section::Pairs,
+            HandFilter::Surrender => return matches!(chart.rows(section)[row][column],
+                                                     ChartAction::SurrenderOrHit | ChartAction::SurrenderOrStand | ChartAction::SurrenderOrSplit),
+        }
+    }
+}
+
+/// Deals random two card hands and upcards until one passes `filter`.
+/// Naturals are never dealt, and `None` is returned if the chart has no
+/// situations that pass.
+pub fn random_situation(filter : HandFilter, chart : &Chart) -> Option<(Vec<char>, char)> {
+    let mut rng = thread_rng();
+    for _draw in 0..MAX_DRAWS {
+        let player_cards: Vec<char> = vec!(SUIT[rng.gen_range(0..SUIT.len())], SUIT[rng.gen_range(0..SUIT.len())]);
+        let upcard: char = UPCARDS[rng.gen_range(0..UPCARDS.len())];
+
+        if let Some((section, row)) = chart::cell(&player_cards, true) {
+            if filter.matches(chart, section, row, chart::upcard_column(upcard)) {
+                return Some((player_cards, upcard));
+            }
+        }
+    }
+    return None;
+}
+
+/// Random cards that make a hand in chart cell `section`, `row`. Two cards
+/// where possible, three for hands like hard 20 that two cards can't make
+/// without a pair.
+pub fn hand_for_cell(section : Section, row : usize) -> Option<Vec<char>> {
+    let mut rng = thread_rng();
+    for num_of_cards in 2..=3 {
+        for _draw in 0..MAX_DRAWS {
+            let player_cards: Vec<char> = (0..num_of_cards).map(|_card| SUIT[rng.gen_range(0..SUIT.len())]).collect();
+            if chart::cell(&player_cards, true) == Some((section, row)) {
+                return Some(player_cards);
+            }
+        }
+    }
+    return None;
+}
+
+/// Cells due for review under `rules` that pass `filter`, most overdue first.
+pub fn due_for_review<'a>(schedule : &'a ReviewSchedule, filter : HandFilter, chart : &Chart, rules : &Rules, today : u64) -> Vec<&'a ReviewItem> {
+    return schedule.due(&rules.code(), today).into_iter()
+                                              .filter(|item| filter.matches(chart, item.section, item.row, item.column))
+                                              .collect();
+}
+
+/// Flashes `rounds` starting hands and grades each action straight away
+/// against the chart or, for composition-dependent strategy, against the
+/// best play from a fresh shoe of `num_of_decks`. Situations in `schedule`
+/// that are due for review come first, and every answer is recorded in
+/// it. `q` stops early.
+pub fn run(filter : HandFilter,
+           rounds : u32,
+           num_of_decks : u8,
+           strategy_mode : StrategyMode,
+           chart : &Chart,
+           rules : &Rules,
+           schedule : &mut ReviewSchedule) -> QuizScore {
+    let mut score: QuizScore = QuizScore::default();
+    let mut dealer_odds: DealerOdds = DealerOdds::new();
+    let today: u64 = review::today();
+
+    let due: Vec<String> = due_for_review(schedule, filter, chart, rules, today).iter().map(|item| item.describe()).collect();
+    if !due.is_empty() {
+        println!("Due for review: {}", due.join(", "));
+    }
+
+    for _round in 0..rounds {
+        let review_cell: Option<(Section, usize, usize)> = due_for_review(schedule, filter, chart, rules, today).first().map(|item| (item.section, item.row, item.column));
+        let situation: Option<(Vec<char>, char)> = match review_cell {
+            Some((section, row, column)) => hand_for_cell(section, row).map(|player_cards| (player_cards, UPCARDS[column])),
+            None => random_situation(filter, chart),
+        };
+        let (player_cards, upcard) = match situation {
+            Some(situation) => situation,
+            None => {
+                println!("The chart has no {:?} situations to drill", filter);
+                break;
+            },
+        };
+
+        let first_two_cards: bool = player_cards.len() == 2;
+        let can_split: bool = first_two_cards && rank_index(player_cards[0]) == rank_index(player_cards[1]);
+        let can_surrender: bool = first_two_cards && rules.late_surrender;
+        let actions: Vec<UserAction> = strategy::available_actions(first_two_cards, can_split, can_surrender);
+
+        let cards: Vec<String> = player_cards.iter().map(|card| card.to_string()).collect();
+        println!("{}{} vs {}", if review_cell.is_some() { "Review: " } else { "" }, cards.join(" "), upcard);
+        print!("(H)it, (S)tand, (D)ouble Down, S(p)lit, Su(r)render, or (Q)uit: ");
+        let input: String = read_user_input();
+        if input == "q" {
+            break;
+        }
+        let action: UserAction = user_input_to_action(&input);
+
+        let correct_action: UserAction = match strategy_mode {
+            StrategyMode::TotalDependent       => chart.lookup(&player_cards, upcard, can_split).resolve(first_two_cards, can_surrender),
+            StrategyMode::CompositionDependent => strategy::composition_dependent_strategy(&player_cards, false, upcard, num_of_decks, &actions, rules, &mut dealer_odds),
+        };
+        score.record(action == correct_action);
+        if action == correct_action {
+            println!("CORRECT! Choice {:?}", correct_action);
+        }
+        else {
+            println!("WRONG! Correct Choice {:?}", correct_action);
+        }
+        if strategy_mode == StrategyMode::TotalDependent {
+            println!("{}", explain::rule_of_thumb(&player_cards, upcard, can_split, correct_action));
+        }
+        if let Some((section, row)) = chart::cell(&player_cards, can_split) {
+            schedule.record(section, row, chart::upcard_column(upcard), &rules.code(), action == correct_action, today);
+        }
+    }
+
+    println!("Flash cards: {}/{} correct ({:.0}%)", score.correct, score.asked, score.accuracy() * 100.0);
+    return score;
+}
+
+
+#[cfg(test)]
+mod flash_tests {
+    use super::*;
+
+    #[test]
+    fn random_situations_pass_the_filter() {
+        let chart = Chart::basic_strategy();
+        for (filter, section) in [(HandFilter::Hard, Section::Hard), (HandFilter::Soft, Section::Soft), (HandFilter::Pairs, Section::Pairs)] {
+            for _situation in 0..50 {
+                let (player_cards, _upcard) = random_situation(filter, &chart).unwrap();
+                assert_eq!(chart::cell(&player_cards, true).unwrap().0, section);
+            }
+        }
+
+        for _situation in 0..50 {
+            let (player_cards, upcard) = random_situation(HandFilter::Surrender, &chart).unwrap();
+            assert!(chart.lookup(&player_cards, upcard, true).code().starts_with('R'));
+        }
+        assert!(random_situation(HandFilter::Surrender, &Chart::new()).is_none());
+    }
+
+    #[test]
+    fn hands_for_every_cell() {
+        for (section, rows) in [(Section::Hard, chart::HARD_TOTALS.len()), (Section::Soft, chart::SOFT_TOTALS.len()), (Section::Pairs, chart::PAIR_CARDS.len())] {
+            for row in 0..rows {
+                let player_cards = hand_for_cell(section, row).unwrap();
+                assert_eq!(chart::cell(&player_cards, true), Some((section, row)));
+            }
+        }
+    }
+
+    #[test]
+    fn reviews_pass_the_filter() {
+        let chart = Chart::basic_strategy();
+        let rules = Rules::default();
+        let mut schedule = ReviewSchedule::new();
+        schedule.record(Section::Hard, 0, 0, &rules.code(), false, 100);
+
+        assert_eq!(due_for_review(&schedule, HandFilter::Hard, &chart, &rules, 101).len(), 1);
+        assert_eq!(due_for_review(&schedule, HandFilter::All, &chart, &rules, 101).len(), 1);
+        assert!(due_for_review(&schedule, HandFilter::Soft, &chart, &rules, 101).is_empty());
+        assert!(due_for_review(&schedule, HandFilter::Pairs, &chart, &rules, 101).is_empty());
+    }
+
+    #[test]
+    fn hand_filter_names() {
+        assert_eq!(HandFilter::from_name("soft"), Some(HandFilter::Soft));
+        assert_eq!(HandFilter::from_name("doubles"), None);
+    }
+}
@@ -0,0 +1,78 @@
+use rand::{thread_rng, Rng};
+
+use crate::drill::flash;
+use crate::game::action::UserAction;
+use crate::game::chart::{Chart, UPCARDS};
+use crate::game::composition::rank_index;
+use crate::game::deviation::{Deviation, IndexTable};
+use crate::game::quiz::QuizScore;
+use crate::game::rules::Rules;
+use crate::input::{read_user_input, user_input_to_action};
+
+/// True counts are drawn in half steps up to this far either side of the
+/// index, so the play is asked for on both sides of it.
+const HALF_STEPS_AROUND_INDEX : i32 = 6;
+
+/// The right play for a hand at `true_count`: the index play if one
+/// applies, otherwise the chart's.
+pub fn correct_action(player_cards : &[char], upcard : char, true_count : f64, index_table : &IndexTable, chart : &Chart, rules : &Rules) -> UserAction {
+    let first_two_cards: bool = player_cards.len() == 2;
+    let can_split: bool = first_two_cards && rank_index(player_cards[0]) == rank_index(player_cards[1]);
+    let can_surrender: bool = first_two_cards && rules.late_surrender;
+    return match index_table.lookup(player_cards, upcard, can_split, true_count) {
+        Some(deviation) => deviation.action.resolve(first_two_cards, can_surrender),
+        None => chart.lookup(player_cards, upcard, can_split).resolve(first_two_cards, can_surrender),
+    };
+}
+
+/// Deals the hands of `index_table` at true counts near their indices and
+/// asks for the play, `rounds` times.
+pub fn run(rounds : u32, index_table : &IndexTable, chart : &Chart, rules : &Rules) -> QuizScore {
+    let mut score: QuizScore = QuizScore::default();
+    let mut rng = thread_rng();
+
+    for _round in 0..rounds {
+        let deviation: &Deviation = match index_table.deviations() {
+            [] => {
+                println!("The index table has no plays to drill");
+                break;
+            },
+            deviations => &deviations[rng.gen_range(0..deviations.len())],
+        };
+        let player_cards: Vec<char> = match flash::hand_for_cell(deviation.section, deviation.row) {
+            Some(player_cards) => player_cards,
+            None => continue,
+        };
+        let upcard: char = UPCARDS[deviation.column];
+        let true_count: f64 = deviation.condition.index() + rng.gen_range(-HALF_STEPS_AROUND_INDEX..=HALF_STEPS_AROUND_INDEX) as f64 / 2.0;
+
+        let cards: Vec<String> = player_cards.iter().map(|card| card.to_string()).collect();
+        println!("{} vs {} at true count {:+.1}", cards.join(" "), upcard, true_count);
+        print!("(H)it, (S)tand, (D)ouble Down, S(p)lit, or Su(r)render: ");
+        let action: UserAction = user_input_to_action(&read_user_input());
+
+        let correct_action: UserAction = correct_action(&player_cards, upcard, true_count, index_table, chart, rules);
+        score.record(action == correct_action);
+        println!("{} Choice {:?}, {}", if action == correct_action { "CORRECT!" } else { "WRONG!" }, correct_action, deviation.describe());
+    }
+
+    println!("Index plays: {}/{} correct ({:.0}%)", score.correct, score.asked, score.accuracy() * 100.0);
+    return score;
+}
+
+
+#[cfg(test)]
+mod indexes_tests {
+    use super::*;
+
+    #[test]
+    fn index_plays_apply_past_the_index() {
+        let table = IndexTable::illustrious_18_fab_4();
+        let chart = Chart::basic_strategy();
+        let rules = Rules::default();
+        assert_eq!(correct_action(&['T', '2'], '3', 2.0, &table, &chart, &rules), UserAction::Stand);
+        assert_eq!(correct_action(&['T', '2'], '3', 1.5, &table, &chart, &rules), UserAction::Hit);
+        assert_eq!(correct_action(&['T', 'T'], '6', 4.0, &table, &chart, &rules), UserAction::Split);
+        assert_eq!(correct_action(&['T', 'T'], '6', 3.5, &table, &chart, &rules), UserAction::Stand);
+    }
+}
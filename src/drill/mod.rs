@@ -0,0 +1,52 @@
+pub mod aces;
+pub mod countdown;
+pub mod curriculum;
+pub mod discard;
+pub mod flash;
+pub mod indexes;
+pub mod ramp;
+pub mod totals;
+pub mod true_count;
+
+use rand::{thread_rng, Rng};
+
+use crate::game::shoe::{Shoe, CARDS_PER_DECK};
+
+/// A fresh shoe of `num_of_decks` with anywhere from half a deck up to
+/// `max_penetration_percentage` of it already dealt, or `None` for a shoe
+/// without any decks.
+pub fn partly_dealt_shoe(num_of_decks : u8, max_penetration_percentage : usize) -> Option<Shoe> {
+    if num_of_decks == 0 {
+        return None;
+    }
+
+    let mut shoe: Shoe = Shoe::new(num_of_decks, 100, Vec::new());
+    shoe.init();
+
+    let total_cards: usize = shoe.cards_remaining();
+    let cards_to_deal: usize = thread_rng().gen_range(CARDS_PER_DECK as usize / 2..=total_cards * max_penetration_percentage / 100);
+    for _card in 0..cards_to_deal {
+        shoe.draw_card();
+    }
+    return Some(shoe);
+}
+
+
+#[cfg(test)]
+mod drill_tests {
+    use super::*;
+
+    #[test]
+    fn shoes_are_dealt_from_half_a_deck_to_the_penetration() {
+        for _shoe in 0..20 {
+            let shoe = partly_dealt_shoe(2, 75).unwrap();
+            assert!(shoe.cards_dealt() >= 26 && shoe.cards_dealt() <= 78);
+            assert_eq!(shoe.seen_cards().len(), shoe.cards_dealt());
+        }
+    }
+
+    #[test]
+    fn shoes_need_a_deck() {
+        assert!(partly_dealt_shoe(0, 75).is_none());
+    }
+}
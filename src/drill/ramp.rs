@@ -0,0 +1,35 @@
+use rand::{thread_rng, Rng};
+
+use crate::game::betting::BetRamp;
+use crate::game::quiz::QuizScore;
+use crate::input::get_user_number;
+
+/// True counts are drawn in tenths between these.
+const MIN_TRUE_COUNT : i32 = -30;
+const MAX_TRUE_COUNT : i32 = 70;
+
+/// Gives a random true count and asks for the bet `ramp` makes at it,
+/// `rounds` times.
+pub fn run(ramp : &BetRamp, rounds : u32) -> QuizScore {
+    let mut score: QuizScore = QuizScore::default();
+
+    for _round in 0..rounds {
+        let true_count: f64 = thread_rng().gen_range(MIN_TRUE_COUNT..=MAX_TRUE_COUNT) as f64 / 10.0;
+        println!("True count {:+.1}", true_count);
+        let answer: Option<f64> = get_user_number("Bet in units? ");
+
+        let units: u32 = ramp.units(true_count);
+        let correct: bool = answer == Some(units as f64);
+        score.record(correct);
+        if correct {
+            println!("CORRECT! The ramp bets {} units", units);
+        }
+        else {
+            println!("WRONG! The ramp bets {} units", units);
+            println!("{}", ramp.describe());
+        }
+    }
+
+    println!("Bets: {}/{} correct ({:.0}%)", score.correct, score.asked, score.accuracy() * 100.0);
+    return score;
+}
@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant};
+
+use rand::{thread_rng, Rng};
+
+use crate::game::hand::Hand;
+use crate::game::quiz::QuizScore;
+use crate::game::shoe::SUIT;
+use crate::input::{get_user_number, read_user_input};
+
+/// Hands are three to this many cards, where totalling gets hard.
+const MAX_CARDS : usize = 6;
+
+/// Draws cards until the hand has `num_of_cards` without busting, starting
+/// over whenever it busts.
+pub fn random_hand(num_of_cards : usize) -> Hand {
+    let mut rng = thread_rng();
+    let mut hand: Hand = Hand::new(Vec::new());
+    while hand.cards.len() < num_of_cards {
+        if !hand.add_card(SUIT[rng.gen_range(0..SUIT.len())]) {
+            hand.reset();
+        }
+    }
+    return hand;
+}
+
+/// An answer is right when both the total and whether the hand is soft are.
+pub fn answer_correct(hand : &Hand, total : Option<f64>, soft : bool) -> bool {
+    return total == Some(hand.total() as f64) && soft == hand.is_soft();
+}
+
+/// Shows `rounds` random hands of several cards and asks for each total
+/// and whether it is soft, timing every answer.
+pub fn run(rounds : u32) -> QuizScore {
+    let mut score: QuizScore = QuizScore::default();
+    let mut time_taken: Duration = Duration::ZERO;
+
+    for _round in 0..rounds {
+        let mut hand: Hand = random_hand(thread_rng().gen_range(3..=MAX_CARDS));
+        hand.display_hand();
+
+        let started: Instant = Instant::now();
+        let total: Option<f64> = get_user_number("Total? ");
+        print!("(S)oft or (H)ard? ");
+        let soft: bool = read_user_input().trim() == "s";
+        let elapsed: Duration = started.elapsed();
+        time_taken += elapsed;
+
+        let correct: bool = answer_correct(&hand, total, soft);
+        score.record(correct);
+        println!("{} {} {} in {:.1}s",
+                 if correct { "CORRECT!" } else { "WRONG!" },
+                 if hand.is_soft() { "Soft" } else { "Hard" },
+                 hand.total(),
+                 elapsed.as_secs_f64());
+    }
+
+    if score.asked > 0 {
+        println!("Hand totals: {}/{} correct ({:.0}%), {:.1}s a hand",
+                 score.correct,
+                 score.asked,
+                 score.accuracy() * 100.0,
+                 time_taken.as_secs_f64() / score.asked as f64);
+    }
+    return score;
+}
+
+
+#[cfg(test)]
+mod totals_tests {
+    use super::*;
+
+    #[test]
+    fn random_hands_never_bust() {
+        for num_of_cards in 3..=MAX_CARDS {
+            for _hand in 0..50 {
+                let mut hand = random_hand(num_of_cards);
+                assert_eq!(hand.cards.len(), num_of_cards);
+                assert!(!hand.is_busted());
+            }
+        }
+    }
+
+    #[test]
+    fn answers_need_total_and_softness() {
+        let mut hand = Hand::new(Vec::new());
+        for card in ['A', '3', '2'] {
+            hand.add_card(card);
+        }
+        assert!(answer_correct(&hand, Some(16.0), true));
+        assert!(!answer_correct(&hand, Some(16.0), false));
+        assert!(!answer_correct(&hand, Some(6.0), true));
+        assert!(!answer_correct(&hand, None, true));
+
+        hand.add_card('T');
+        assert!(answer_correct(&hand, Some(16.0), false));
+    }
+}
@@ -0,0 +1,40 @@
+use crate::drill::discard::render_tray;
+use crate::drill::partly_dealt_shoe;
+use crate::game::quiz::{self, QuizScore};
+use crate::game::shoe::Shoe;
+use crate::input::get_user_number;
+
+/// True counts are asked with no less than a fifth of the shoe left.
+const MAX_PENETRATION_PERCENTAGE : usize = 80;
+
+/// Deals a random part of a fresh shoe, gives the running count and shows
+/// the discard tray, then asks for the true count, `rounds` times.
+pub fn run(num_of_decks : u8, rounds : u32) -> QuizScore {
+    let mut score: QuizScore = QuizScore::default();
+
+    for _round in 0..rounds {
+        let shoe: Shoe = match partly_dealt_shoe(num_of_decks, MAX_PENETRATION_PERCENTAGE) {
+            Some(shoe) => shoe,
+            None => {
+                println!("A shoe needs at least one deck");
+                return score;
+            },
+        };
+
+        println!("{} deck shoe, running count {:+}, discard tray:", num_of_decks, shoe.running_count());
+        print!("{}", render_tray(shoe.cards_dealt(), num_of_decks));
+
+        let true_count: f64 = shoe.true_count();
+        let answer: Option<f64> = get_user_number("What is the true count? ");
+        let correct: bool = quiz::true_count_correct(answer.unwrap_or(f64::NAN), true_count);
+        score.record(correct);
+        println!("{} True count {:+.1}: running count {:+} over {:.2} decks left",
+                 if correct { "CORRECT!" } else { "WRONG!" },
+                 true_count,
+                 shoe.running_count(),
+                 shoe.decks_remaining());
+    }
+
+    println!("True counts: {}/{} correct ({:.0}%)", score.correct, score.asked, score.accuracy() * 100.0);
+    return score;
+}
@@ -0,0 +1,10 @@
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UserAction {
+    Hit,
+    Stand,
+    DoubleDown,
+    Split,
+    Surrender,
+    Unknown,
+}
@@ -0,0 +1,112 @@
+/// A 1 to 12 unit Hi-Lo spread for a six deck game. Each line is the true
+/// count a bet starts at and the bet in units.
+pub const DEFAULT_BET_RAMP : &str = "
+1 1
+2 2
+3 4
+4 8
+5 12
+";
+
+/// Hi-Lo players give up about half a percent off the top of a six deck
+/// shoe and gain about half a percent per true count.
+const ADVANTAGE_OFF_THE_TOP    : f64 = -0.005;
+const ADVANTAGE_PER_TRUE_COUNT : f64 = 0.005;
+
+/// Variance of a blackjack hand in squared units.
+const HAND_VARIANCE : f64 = 1.33;
+
+/// Bets in units keyed on the true count.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BetRamp {
+    steps : Vec<(f64, u32)>,
+}
+
+impl BetRamp {
+    pub fn default_ramp() -> BetRamp {
+        return BetRamp::parse(DEFAULT_BET_RAMP).unwrap();
+    }
+
+    /// Parses lines of `true-count units`. Below the lowest true count the
+    /// lowest bet is made.
+    pub fn parse(text : &str) -> Result<BetRamp, String> {
+        let mut steps: Vec<(f64, u32)> = Vec::new();
+
+        for line in text.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.is_empty() || fields[0].starts_with('#') {
+                continue;
+            }
+            if fields.len() != 2 {
+                return Err(format!("ramp line '{}' should be a true count and a bet", line.trim()));
+            }
+            let true_count: f64 = fields[0].parse().map_err(|_error| format!("true count '{}' is not a number", fields[0]))?;
+            let units: u32 = fields[1].parse().map_err(|_error| format!("bet '{}' is not a whole number of units", fields[1]))?;
+            steps.push((true_count, units));
+        }
+
+        if steps.is_empty() {
+            return Err("bet ramp has no steps".to_string());
+        }
+        steps.sort_by(|a, b| a.0.total_cmp(&b.0));
+        return Ok(BetRamp { steps });
+    }
+
+    pub fn units(&self, true_count : f64) -> u32 {
+        let mut units: u32 = self.steps[0].1;
+        for (step, step_units) in self.steps.iter() {
+            if true_count >= *step {
+                units = *step_units;
+            }
+        }
+        return units;
+    }
+
+    pub fn describe(&self) -> String {
+        let steps: Vec<String> = self.steps.iter().map(|(true_count, units)| format!("{} at {:+}", units, true_count)).collect();
+        return format!("Bet ramp (units at true count): {}", steps.join(", "));
+    }
+}
+
+/// Rough player advantage at `true_count`, as a fraction of the bet.
+pub fn estimated_advantage(true_count : f64) -> f64 {
+    return ADVANTAGE_OFF_THE_TOP + ADVANTAGE_PER_TRUE_COUNT * true_count;
+}
+
+/// Kelly bet for `advantage`, in the same units as `bankroll`. Nothing is
+/// bet without an advantage.
+pub fn kelly_bet(advantage : f64, bankroll : f64) -> f64 {
+    if advantage <= 0.0 {
+        return 0.0;
+    }
+    return bankroll * advantage / HAND_VARIANCE;
+}
+
+
+#[cfg(test)]
+mod betting_tests {
+    use super::*;
+
+    #[test]
+    fn bet_ramp_units() {
+        let ramp = BetRamp::default_ramp();
+        assert_eq!(ramp.units(-3.0), 1);
+        assert_eq!(ramp.units(1.9), 1);
+        assert_eq!(ramp.units(2.0), 2);
+        assert_eq!(ramp.units(4.5), 8);
+        assert_eq!(ramp.units(9.0), 12);
+
+        let ramp = BetRamp::parse("3 6\n# wong in\n0 1\n").unwrap();
+        assert_eq!(ramp.units(-1.0), 1);
+        assert_eq!(ramp.units(3.0), 6);
+        assert!(BetRamp::parse("").is_err());
+        assert!(BetRamp::parse("2 1.5\n").is_err());
+    }
+
+    #[test]
+    fn kelly_bet_follows_advantage() {
+        assert_eq!(kelly_bet(estimated_advantage(0.0), 1000.0), 0.0);
+        assert!((estimated_advantage(3.0) - 0.01).abs() < 1e-12);
+        assert!((kelly_bet(0.01, 1000.0) - 7.518796992).abs() < 1e-6);
+    }
+}
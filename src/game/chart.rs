@@ -0,0 +1,353 @@
+use crate::game::action::UserAction;
+use crate::game::composition::{ACE_RANK, rank_index, rank_value};
+
+const BLACKJACK : u8 = 21;
+
+pub const NUM_OF_UPCARDS : usize = 10;
+pub const UPCARDS        : [char; NUM_OF_UPCARDS] = ['2', '3', '4', '5', '6', '7', '8', '9', 'T', 'A'];
+
+pub const HARD_TOTALS : [u8; 16] = [5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20];
+pub const SOFT_TOTALS : [u8; 8]  = [13, 14, 15, 16, 17, 18, 19, 20];
+pub const PAIR_CARDS  : [char; 10] = ['2', '3', '4', '5', '6', '7', '8', '9', 'T', 'A'];
+
+/// Basic strategy for four to eight decks, dealer stands on soft 17,
+/// double after split and late surrender allowed.
+pub const BASIC_STRATEGY : &str = "
+Hard  2  3  4  5  6  7  8  9  T  A
+5     H  H  H  H  H  H  H  H  H  H
+6     H  H  H  H  H  H  H  H  H  H
+7     H  H  H  H  H  H  H  H  H  H
+8     H  H  H  H  H  H  H  H  H  H
+9     H  D  D  D  D  H  H  H  H  H
+10    D  D  D  D  D  D  D  D  H  H
+11    D  D  D  D  D  D  D  D  D  H
+12    H  H  S  S  S  H  H  H  H  H
+13    S  S  S  S  S  H  H  H  H  H
+14    S  S  S  S  S  H  H  H  H  H
+15    S  S  S  S  S  H  H  H  Rh H
+16    S  S  S  S  S  H  H  Rh Rh Rh
+17    S  S  S  S  S  S  S  S  S  S
+18    S  S  S  S  S  S  S  S  S  S
+19    S  S  S  S  S  S  S  S  S  S
+20    S  S  S  S  S  S  S  S  S  S
+
+Soft  2  3  4  5  6  7  8  9  T  A
+A2    H  H  H  D  D  H  H  H  H  H
+A3    H  H  H  D  D  H  H  H  H  H
+A4    H  H  D  D  D  H  H  H  H  H
+A5    H  H  D  D  D  H  H  H  H  H
+A6    H  D  D  D  D  H  H  H  H  H
+A7    S  Ds Ds Ds Ds S  S  H  H  H
+A8    S  S  S  S  S  S  S  S  S  S
+A9    S  S  S  S  S  S  S  S  S  S
+
+Pairs 2  3  4  5  6  7  8  9  T  A
+22    P  P  P  P  P  P  H  H  H  H
+33    P  P  P  P  P  P  H  H  H  H
+44    H  H  H  P  P  H  H  H  H  H
+55    D  D  D  D  D  D  D  D  H  H
+66    P  P  P  P  P  H  H  H  H  H
+77    P  P  P  P  P  P  H  H  H  H
+88    P  P  P  P  P  P  P  P  P  P
+99    P  P  P  P  P  S  P  P  S  S
+TT    S  S  S  S  S  S  S  S  S  S
+AA    P  P  P  P  P  P  P  P  P  P
+";
+
+/// One cell of a strategy chart. The double and surrender codes fall back
+/// to the second action when the first isn't allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChartAction {
+    Hit,
+    Stand,
+    DoubleOrHit,
+    DoubleOrStand,
+    Split,
+    SurrenderOrHit,
+    SurrenderOrStand,
+    SurrenderOrSplit,
+}
+
+impl ChartAction {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ChartAction::Hit              => "H",
+            ChartAction::Stand            => "S",
+            ChartAction::DoubleOrHit      => "D",
+            ChartAction::DoubleOrStand    => "Ds",
+            ChartAction::Split            => "P",
+            ChartAction::SurrenderOrHit   => "Rh",
+            ChartAction::SurrenderOrStand => "Rs",
+            ChartAction::SurrenderOrSplit => "Rp",
+        }
+    }
+
+    pub fn from_code(code : &str) -> Option<ChartAction> {
+        match code {
+            "H"  => Some(ChartAction::Hit),
+            "S"  => Some(ChartAction::Stand),
+            "D"  => Some(ChartAction::DoubleOrHit),
+            "Ds" => Some(ChartAction::DoubleOrStand),
+            "P"  => Some(ChartAction::Split),
+            "Rh" => Some(ChartAction::SurrenderOrHit),
+            "Rs" => Some(ChartAction::SurrenderOrStand),
+            "Rp" => Some(ChartAction::SurrenderOrSplit),
+            _=> None,
+        }
+    }
+
+    /// The action to take given what the hand is currently allowed to do.
+    pub fn resolve(&self, can_double : bool, can_surrender : bool) -> UserAction {
+        match self {
+            ChartAction::Hit              => UserAction::Hit,
+            ChartAction::Stand            => UserAction::Stand,
+            ChartAction::DoubleOrHit      => if can_double { UserAction::DoubleDown } else { UserAction::Hit },
+            ChartAction::DoubleOrStand    => if can_double { UserAction::DoubleDown } else { UserAction::Stand },
+            ChartAction::Split            => UserAction::Split,
+            ChartAction::SurrenderOrHit   => if can_surrender { UserAction::Surrender } else { UserAction::Hit },
+            ChartAction::SurrenderOrStand => if can_surrender { UserAction::Surrender } else { UserAction::Stand },
+            ChartAction::SurrenderOrSplit => if can_surrender { UserAction::Surrender } else { UserAction::Split },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Section {
+    Hard,
+    Soft,
+    Pairs,
+}
+
+/// A basic strategy chart: hard totals, soft totals and pairs down the side,
+/// dealer upcards 2 through ace across the top.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chart {
+    hard  : [[ChartAction; NUM_OF_UPCARDS]; HARD_TOTALS.len()],
+    soft  : [[ChartAction; NUM_OF_UPCARDS]; SOFT_TOTALS.len()],
+    pairs : [[ChartAction; NUM_OF_UPCARDS]; PAIR_CARDS.len()],
+}
+
+impl Chart {
+    /// A chart that stands everywhere, for filling in one cell at a time.
+    pub fn new() -> Chart {
+        Chart {
+            hard  : [[ChartAction::Stand; NUM_OF_UPCARDS]; HARD_TOTALS.len()],
+            soft  : [[ChartAction::Stand; NUM_OF_UPCARDS]; SOFT_TOTALS.len()],
+            pairs : [[ChartAction::Stand; NUM_OF_UPCARDS]; PAIR_CARDS.len()],
+        }
+    }
+
+    pub fn basic_strategy() -> Chart {
+        return Chart::parse(BASIC_STRATEGY).expect("built in chart is valid");
+    }
+
+    pub fn rows(&self, section : Section) -> &[[ChartAction; NUM_OF_UPCARDS]] {
+        match section {
+            Section::Hard  => &self.hard,
+            Section::Soft  => &self.soft,
+            Section::Pairs => &self.pairs,
+        }
+    }
+
+    pub fn set(&mut self, section : Section, row : usize, column : usize, action : ChartAction) {
+        match section {
+            Section::Hard  => self.hard[row][column] = action,
+            Section::Soft  => self.soft[row][column] = action,
+            Section::Pairs => self.pairs[row][column] = action,
+        }
+    }
+
+    /// The chart cell covering `player_cards` against `upcard`. Pairs are only
+    /// looked up in the pairs section while the hand is still allowed to split.
+    pub fn lookup(&self, player_cards : &[char], upcard : char, can_split : bool) -> ChartAction {
+        let column: usize = upcard_column(upcard);
+        match cell(player_cards, can_split) {
+            Some((section, row)) => return self.rows(section)[row][column],
+            None => {
+                let (hard_total, has_ace) = hard_total(player_cards);
+                let soft: bool = has_ace && hard_total + 10 <= BLACKJACK;
+                if hard_total >= BLACKJACK || (soft && hard_total + 10 == BLACKJACK) {
+                    return ChartAction::Stand;
+                }
+                return ChartAction::Hit;
+            }
+        }
+    }
+
+    /// Parses the text format written by `to_text`: a header line naming the
+    /// section, then one row per hand with a code for each upcard.
+    pub fn parse(text : &str) -> Result<Chart, String> {
+        let mut chart: Chart = Chart::new();
+        let mut section: Option<Section> = None;
+        let mut rows_seen: usize = 0;
+
+        for line in text.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.is_empty() || fields[0].starts_with('#') {
+                continue;
+            }
+
+            match fields[0] {
+                "Hard"  => { section = Some(Section::Hard); continue; },
+                "Soft"  => { section = Some(Section::Soft); continue; },
+                "Pairs" => { section = Some(Section::Pairs); continue; },
+                _=> (),
+            }
+
+            let section: Section = section.ok_or(format!("row '{}' appears before a section header", fields[0]))?;
+            let row: usize = row_index(section, fields[0]).ok_or(format!("unknown {:?} row '{}'", section, fields[0]))?;
+            if fields.len() != NUM_OF_UPCARDS + 1 {
+                return Err(format!("row '{}' should have {} entries", fields[0], NUM_OF_UPCARDS));
+            }
+            for (column, code) in fields[1..].iter().enumerate() {
+                let action: ChartAction = ChartAction::from_code(code).ok_or(format!("unknown action '{}' in row '{}'", code, fields[0]))?;
+                chart.set(section, row, column, action);
+            }
+            rows_seen += 1;
+        }
+
+        if rows_seen != HARD_TOTALS.len() + SOFT_TOTALS.len() + PAIR_CARDS.len() {
+            return Err(format!("chart has {} rows, expected {}", rows_seen, HARD_TOTALS.len() + SOFT_TOTALS.len() + PAIR_CARDS.len()));
+        }
+        return Ok(chart);
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text: String = String::new();
+        for section in [Section::Hard, Section::Soft, Section::Pairs] {
+            text += &format!("{:<5}", section_name(section));
+            for upcard in UPCARDS {
+                text += &format!(" {:<2}", upcard);
+            }
+            text = text.trim_end().to_string() + "\n";
+
+            for (row, actions) in self.rows(section).iter().enumerate() {
+                text += &format!("{:<5}", row_label(section, row));
+                for action in actions {
+                    text += &format!(" {:<2}", action.code());
+                }
+                text = text.trim_end().to_string() + "\n";
+            }
+            text += "\n";
+        }
+        return text;
+    }
+}
+
+impl Default for Chart {
+    fn default() -> Chart {
+        Chart::new()
+    }
+}
+
+pub fn section_name(section : Section) -> &'static str {
+    match section {
+        Section::Hard  => "Hard",
+        Section::Soft  => "Soft",
+        Section::Pairs => "Pairs",
+    }
+}
+
+pub fn row_label(section : Section, row : usize) -> String {
+    match section {
+        Section::Hard  => HARD_TOTALS[row].to_string(),
+        Section::Soft  => format!("A{}", SOFT_TOTALS[row] - 11),
+        Section::Pairs => format!("{}{}", PAIR_CARDS[row], PAIR_CARDS[row]),
+    }
+}
+
+fn row_index(section : Section, label : &str) -> Option<usize> {
+    let rows: usize = match section {
+        Section::Hard  => HARD_TOTALS.len(),
+        Section::Soft  => SOFT_TOTALS.len(),
+        Section::Pairs => PAIR_CARDS.len(),
+    };
+    return (0..rows).find(|row| row_label(section, *row) == label);
+}
+
+/// The section and row of a hand written as a row label, e.g. "16", "A7" or "88".
+pub fn label_cell(label : &str) -> Option<(Section, usize)> {
+    for section in [Section::Hard, Section::Soft, Section::Pairs] {
+        if let Some(row) = row_index(section, label) {
+            return Some((section, row));
+        }
+    }
+    return None;
+}
+
+pub fn upcard_column(upcard : char) -> usize {
+    let rank: usize = rank_index(upcard);
+    if rank == ACE_RANK {
+        return NUM_OF_UPCARDS - 1;
+    }
+    return rank - 1;
+}
+
+/// The section and row of the chart that covers `player_cards`, or `None`
+/// for hands the chart doesn't list (soft 12, 21 and over).
+pub fn cell(player_cards : &[char], can_split : bool) -> Option<(Section, usize)> {
+    if can_split && player_cards.len() == 2 && rank_index(player_cards[0]) == rank_index(player_cards[1]) {
+        let pair_rank: usize = rank_index(player_cards[0]);
+        let row: usize = if pair_rank == ACE_RANK { PAIR_CARDS.len() - 1 } else { pair_rank - 1 };
+        return Some((Section::Pairs, row));
+    }
+
+    let (hard_total, has_ace) = hard_total(player_cards);
+    if has_ace && hard_total + 10 <= BLACKJACK {
+        let soft_total: u8 = hard_total + 10;
+        return SOFT_TOTALS.iter().position(|total| *total == soft_total).map(|row| (Section::Soft, row));
+    }
+    if hard_total < HARD_TOTALS[0] {
+        return Some((Section::Hard, 0));
+    }
+    return HARD_TOTALS.iter().position(|total| *total == hard_total).map(|row| (Section::Hard, row));
+}
+
+fn hard_total(player_cards : &[char]) -> (u8, bool) {
+    let mut total: u8 = 0;
+    let mut has_ace: bool = false;
+    for card in player_cards {
+        let rank: usize = rank_index(*card);
+        total += rank_value(rank);
+        has_ace |= rank == ACE_RANK;
+    }
+    return (total, has_ace);
+}
+
+
+#[cfg(test)]
+mod chart_tests {
+    use super::*;
+
+    #[test]
+    fn chart_round_trips_through_text() {
+        let chart = Chart::basic_strategy();
+        assert_eq!(Chart::parse(&chart.to_text()), Ok(chart));
+    }
+
+    #[test]
+    fn chart_lookup() {
+        let chart = Chart::basic_strategy();
+        assert_eq!(chart.lookup(&['T', '6'], 'T', true), ChartAction::SurrenderOrHit);
+        assert_eq!(chart.lookup(&['T', '2'], '4', true), ChartAction::Stand);
+        assert_eq!(chart.lookup(&['A', '7'], '3', true), ChartAction::DoubleOrStand);
+        assert_eq!(chart.lookup(&['8', '8'], 'A', true), ChartAction::Split);
+        assert_eq!(chart.lookup(&['8', '8'], 'A', false), ChartAction::SurrenderOrHit);
+        assert_eq!(chart.lookup(&['A', 'A'], '6', false), ChartAction::Hit);
+        assert_eq!(chart.lookup(&['T', '5', '6'], '6', false), ChartAction::Stand);
+    }
+
+    #[test]
+    fn chart_action_falls_back() {
+        assert_eq!(ChartAction::DoubleOrStand.resolve(false, true), UserAction::Stand);
+        assert_eq!(ChartAction::DoubleOrHit.resolve(true, true), UserAction::DoubleDown);
+        assert_eq!(ChartAction::SurrenderOrSplit.resolve(true, false), UserAction::Split);
+    }
+
+    #[test]
+    fn chart_parse_rejects_bad_rows() {
+        assert!(Chart::parse("Hard 2 3 4 5 6 7 8 9 T A\n5 H H H").is_err());
+        assert!(Chart::parse("5 H H H H H H H H H H").is_err());
+        assert!(Chart::parse("Hard 2 3 4 5 6 7 8 9 T A\n5 H H H H H H H H H X").is_err());
+    }
+}
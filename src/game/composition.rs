@@ -0,0 +1,154 @@
+
+pub const NUM_OF_RANKS : usize = 10;
+
+pub const ACE_RANK : usize = 0;
+pub const TEN_RANK : usize = 9;
+
+/// Number of cards of each rank left to be drawn, indexed by rank
+/// (ace first, then 2 through 9, with all ten-valued cards sharing one rank).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Composition {
+    counts   : [u16; NUM_OF_RANKS],
+    total    : u16,
+    infinite : bool,
+}
+
+impl Composition {
+    pub fn new() -> Composition {
+        Composition {
+            counts   : [0; NUM_OF_RANKS],
+            total    : 0,
+            infinite : false,
+        }
+    }
+
+    /// An infinite deck, where drawing a card never changes the odds of the next one.
+    pub fn infinite_deck() -> Composition {
+        let mut composition: Composition = Composition::full_shoe(1);
+        composition.infinite = true;
+        return composition;
+    }
+
+    /// Composition of `num_of_decks` full decks.
+    pub fn full_shoe(num_of_decks : u8) -> Composition {
+        let mut composition: Composition = Composition::new();
+        for rank in 0..NUM_OF_RANKS {
+            let cards_per_deck: u16 = if rank == TEN_RANK { 16 } else { 4 };
+            composition.counts[rank] = cards_per_deck * num_of_decks as u16;
+            composition.total += composition.counts[rank];
+        }
+        return composition;
+    }
+
+    pub fn from_cards(cards : &[char]) -> Composition {
+        let mut composition: Composition = Composition::new();
+        for card in cards {
+            composition.add(rank_index(*card));
+        }
+        return composition;
+    }
+
+    pub fn count(&self, rank : usize) -> u16 {
+        return self.counts[rank];
+    }
+
+    pub fn total(&self) -> u16 {
+        return self.total;
+    }
+
+    pub fn probability(&self, rank : usize) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        return self.counts[rank] as f64 / self.total as f64;
+    }
+
+    pub fn add(&mut self, rank : usize) {
+        if self.infinite {
+            return;
+        }
+        self.counts[rank] += 1;
+        self.total += 1;
+    }
+
+    pub fn remove(&mut self, rank : usize) {
+        if self.infinite {
+            return;
+        }
+        if self.counts[rank] == 0 {
+            panic!("no cards of rank {} left to remove", rank);
+        }
+        self.counts[rank] -= 1;
+        self.total -= 1;
+    }
+}
+
+impl Default for Composition {
+    fn default() -> Composition {
+        Composition::new()
+    }
+}
+
+pub fn rank_index(card : char) -> usize {
+    match card {
+        'A' => 0,
+        '2' => 1,
+        '3' => 2,
+        '4' => 3,
+        '5' => 4,
+        '6' => 5,
+        '7' => 6,
+        '8' => 7,
+        '9' => 8,
+        'T' | 'J' | 'Q' | 'K' => 9,
+        _=> panic!("Invalid card provided"),
+    }
+}
+
+/// Hard value of a rank, counting the ace as one.
+pub fn rank_value(rank : usize) -> u8 {
+    return rank as u8 + 1;
+}
+
+
+#[cfg(test)]
+mod composition_tests {
+    use super::*;
+
+    #[test]
+    fn composition_from_cards() {
+        let composition = Composition::from_cards(&['A', 'K', 'T', '5', 'Q']);
+        assert_eq!(composition.total(), 5);
+        assert_eq!(composition.count(ACE_RANK), 1);
+        assert_eq!(composition.count(TEN_RANK), 3);
+        assert_eq!(composition.count(rank_index('5')), 1);
+        assert_eq!(composition.probability(TEN_RANK), 0.6);
+    }
+
+    #[test]
+    fn composition_full_shoe() {
+        let composition = Composition::full_shoe(2);
+        assert_eq!(composition.total(), 104);
+        assert_eq!(composition.count(ACE_RANK), 8);
+        assert_eq!(composition.count(TEN_RANK), 32);
+    }
+
+    #[test]
+    fn composition_infinite_deck() {
+        let mut composition = Composition::infinite_deck();
+        composition.remove(TEN_RANK);
+        composition.remove(TEN_RANK);
+        assert_eq!(composition.probability(TEN_RANK), 4.0 / 13.0);
+    }
+
+    #[test]
+    fn composition_add_remove() {
+        let mut composition = Composition::new();
+        composition.add(rank_index('7'));
+        composition.add(rank_index('7'));
+        composition.remove(rank_index('7'));
+        assert_eq!(composition.total(), 1);
+        assert_eq!(composition.count(rank_index('7')), 1);
+        assert_eq!(rank_value(ACE_RANK), 1);
+    }
+}
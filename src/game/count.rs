@@ -0,0 +1,288 @@
+use crate::game::composition::{NUM_OF_RANKS, rank_index};
+
+const CARDS_PER_RANK_PER_DECK : [u8; NUM_OF_RANKS] = [4, 4, 4, 4, 4, 4, 4, 4, 4, 16];
+
+/// Order of the tag values in a counting system file, the same as the chart columns.
+const TAG_ORDER : [char; NUM_OF_RANKS] = ['2', '3', '4', '5', '6', '7', '8', '9', 'T', 'A'];
+
+/// A card counting system: the value added to the running count for each
+/// rank, and whether aces are kept in a separate side count.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CountingSystem {
+    name           : String,
+    tags           : [f64; NUM_OF_RANKS],
+    ace_side_count : bool,
+}
+
+impl CountingSystem {
+    /// Tags are given in the order 2 through 9, ten, ace.
+    pub fn new(name : &str, tags : [f64; NUM_OF_RANKS], ace_side_count : bool) -> CountingSystem {
+        let mut rank_tags: [f64; NUM_OF_RANKS] = [0.0; NUM_OF_RANKS];
+        for (card, tag) in TAG_ORDER.iter().zip(tags.iter()) {
+            rank_tags[rank_index(*card)] = *tag;
+        }
+
+        CountingSystem {
+            name : name.to_string(),
+            tags : rank_tags,
+            ace_side_count,
+        }
+    }
+
+    pub fn hi_lo() -> CountingSystem {
+        return CountingSystem::new("Hi-Lo", [1.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, -1.0, -1.0], false);
+    }
+
+    pub fn ko() -> CountingSystem {
+        return CountingSystem::new("KO", [1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, -1.0, -1.0], false);
+    }
+
+    pub fn hi_opt_1() -> CountingSystem {
+        return CountingSystem::new("Hi-Opt I", [0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, -1.0, 0.0], true);
+    }
+
+    pub fn hi_opt_2() -> CountingSystem {
+        return CountingSystem::new("Hi-Opt II", [1.0, 1.0, 2.0, 2.0, 1.0, 1.0, 0.0, 0.0, -2.0, 0.0], true);
+    }
+
+    pub fn omega_2() -> CountingSystem {
+        return CountingSystem::new("Omega II", [1.0, 1.0, 2.0, 2.0, 2.0, 1.0, 0.0, -1.0, -2.0, 0.0], true);
+    }
+
+    pub fn zen() -> CountingSystem {
+        return CountingSystem::new("Zen", [1.0, 1.0, 2.0, 2.0, 2.0, 1.0, 0.0, 0.0, -2.0, -1.0], false);
+    }
+
+    pub fn wong_halves() -> CountingSystem {
+        return CountingSystem::new("Wong Halves", [0.5, 1.0, 1.0, 1.5, 1.0, 0.5, 0.0, -0.5, -1.0, -1.0], false);
+    }
+
+    /// A built in system by its command line name, e.g. `hi-lo` or `omega-2`.
+    pub fn from_name(name : &str) -> Option<CountingSystem> {
+        match name {
+            "hi-lo"    => Some(CountingSystem::hi_lo()),
+            "ko"       => Some(CountingSystem::ko()),
+            "hi-opt-1" => Some(CountingSystem::hi_opt_1()),
+            "hi-opt-2" => Some(CountingSystem::hi_opt_2()),
+            "omega-2"  => Some(CountingSystem::omega_2()),
+            "zen"      => Some(CountingSystem::zen()),
+            "halves"   => Some(CountingSystem::wong_halves()),
+            _=> None,
+        }
+    }
+
+    /// Reads a system written as a `name` line, a `tags` line with values
+    /// for 2 through 9, ten and ace, and optionally a `side-count aces` line.
+    pub fn parse(text : &str) -> Result<CountingSystem, String> {
+        let mut name: Option<String> = None;
+        let mut tags: Option<[f64; NUM_OF_RANKS]> = None;
+        let mut ace_side_count: bool = false;
+
+        for line in text.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.is_empty() || fields[0].starts_with('#') {
+                continue;
+            }
+
+            match fields[0] {
+                "name" => name = Some(fields[1..].join(" ")),
+                "tags" => {
+                    if fields.len() != NUM_OF_RANKS + 1 {
+                        return Err(format!("tags should have {} values, for 2 through 9, T and A", NUM_OF_RANKS));
+                    }
+                    let mut values: [f64; NUM_OF_RANKS] = [0.0; NUM_OF_RANKS];
+                    for (value, field) in values.iter_mut().zip(fields[1..].iter()) {
+                        *value = field.parse().map_err(|_error| format!("tag '{}' is not a number", field))?;
+                    }
+                    tags = Some(values);
+                },
+                "side-count" => {
+                    if fields[1..] != ["aces"] {
+                        return Err(format!("unknown side count '{}'", fields[1..].join(" ")));
+                    }
+                    ace_side_count = true;
+                },
+                _=> return Err(format!("unknown line '{}'", line.trim())),
+            }
+        }
+
+        let name: String = name.ok_or("counting system has no name")?;
+        let tags: [f64; NUM_OF_RANKS] = tags.ok_or("counting system has no tags")?;
+        return Ok(CountingSystem::new(&name, tags, ace_side_count));
+    }
+
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+
+    pub fn tag(&self, card : char) -> f64 {
+        return self.tags[rank_index(card)];
+    }
+
+    pub fn has_ace_side_count(&self) -> bool {
+        return self.ace_side_count;
+    }
+
+    /// What a full deck adds to the running count, zero for a balanced system.
+    pub fn imbalance(&self) -> f64 {
+        return self.tags.iter()
+                        .zip(CARDS_PER_RANK_PER_DECK.iter())
+                        .map(|(tag, cards)| tag * *cards as f64)
+                        .sum();
+    }
+
+    /// What each ace left in the shoe beyond its share is worth to the
+    /// running count when betting: as much as a ten, less what its tag
+    /// already accounts for.
+    pub fn ace_betting_weight(&self) -> f64 {
+        return self.tag('A') - self.tag('T');
+    }
+
+    /// Insurance only depends on the tens, so for insurance each extra ace
+    /// left in the shoe takes back the tag the count gave it.
+    pub fn ace_insurance_weight(&self) -> f64 {
+        return self.tag('A');
+    }
+
+    pub fn is_balanced(&self) -> bool {
+        return self.imbalance() == 0.0;
+    }
+
+    /// Unbalanced systems start below zero so that the count finishes the
+    /// shoe at the imbalance of a single deck, e.g. +4 for KO.
+    pub fn initial_running_count(&self, num_of_decks : u8) -> f64 {
+        if self.is_balanced() {
+            return 0.0;
+        }
+        return -self.imbalance() * (num_of_decks as f64 - 1.0);
+    }
+}
+
+impl Default for CountingSystem {
+    fn default() -> CountingSystem {
+        return CountingSystem::hi_lo();
+    }
+}
+
+/// Running count of the cards seen since the last shuffle.
+#[derive(Debug, Clone, Default)]
+pub struct Counter {
+    system        : CountingSystem,
+    num_of_decks  : u8,
+    running_count : f64,
+}
+
+impl Counter {
+    pub fn new(system : CountingSystem, num_of_decks : u8) -> Counter {
+        let running_count: f64 = system.initial_running_count(num_of_decks);
+
+        Counter {
+            system,
+            num_of_decks,
+            running_count,
+        }
+    }
+
+    pub fn system(&self) -> &CountingSystem {
+        return &self.system;
+    }
+
+    pub fn count(&mut self, card : char) {
+        self.running_count += self.system.tag(card);
+    }
+
+    pub fn running_count(&self) -> f64 {
+        return self.running_count;
+    }
+
+    /// Running count per deck left to be played. Only meaningful for
+    /// balanced systems; unbalanced ones are played off the running count.
+    pub fn true_count(&self, decks_remaining : f64) -> f64 {
+        if decks_remaining <= 0.0 {
+            return self.running_count;
+        }
+        return self.running_count / decks_remaining;
+    }
+
+    pub fn reset(&mut self) {
+        self.running_count = self.system.initial_running_count(self.num_of_decks);
+    }
+}
+
+
+#[cfg(test)]
+mod count_tests {
+    use super::*;
+    use crate::game::shoe::SUIT;
+
+    fn count_decks(counter : &mut Counter, num_of_decks : u8) {
+        for _suit in 0..num_of_decks * 4 {
+            for card in SUIT {
+                counter.count(card);
+            }
+        }
+    }
+
+    #[test]
+    fn built_in_systems_balance() {
+        for name in ["hi-lo", "hi-opt-1", "hi-opt-2", "omega-2", "zen", "halves"] {
+            let system = CountingSystem::from_name(name).unwrap();
+            assert!(system.is_balanced(), "{} should be balanced", system.name());
+
+            let mut counter = Counter::new(system, 1);
+            count_decks(&mut counter, 1);
+            assert_eq!(counter.running_count(), 0.0);
+        }
+        assert!(!CountingSystem::ko().is_balanced());
+    }
+
+    #[test]
+    fn ko_starts_below_zero() {
+        let mut counter = Counter::new(CountingSystem::ko(), 6);
+        assert_eq!(counter.running_count(), -20.0);
+        count_decks(&mut counter, 6);
+        assert_eq!(counter.running_count(), 4.0);
+
+        counter.reset();
+        assert_eq!(counter.running_count(), -20.0);
+    }
+
+    #[test]
+    fn true_count_divides_by_decks_remaining() {
+        let mut counter = Counter::new(CountingSystem::hi_lo(), 1);
+        for card in ['2', '3', '5', '6', '4', '7'] {
+            counter.count(card);
+        }
+        assert_eq!(counter.running_count(), 5.0);
+        assert_eq!(counter.true_count(2.5), 2.0);
+        assert_eq!(counter.true_count(0.0), 5.0);
+
+        counter.reset();
+        assert_eq!(counter.running_count(), 0.0);
+    }
+
+    #[test]
+    fn ace_weights() {
+        assert_eq!(CountingSystem::hi_opt_2().ace_betting_weight(), 2.0);
+        assert_eq!(CountingSystem::hi_opt_2().ace_insurance_weight(), 0.0);
+        assert_eq!(CountingSystem::hi_lo().ace_betting_weight(), 0.0);
+        assert_eq!(CountingSystem::hi_lo().ace_insurance_weight(), -1.0);
+    }
+
+    #[test]
+    fn parse_counting_system() {
+        let text = "# Like KO, but sevens only count half\n\
+                    name Half Seven\n\
+                    tags 1 1 1 1 1 0.5 0 0 -1 -1\n";
+        let system = CountingSystem::parse(text).unwrap();
+        assert_eq!(system.name(), "Half Seven");
+        assert_eq!(system.tag('7'), 0.5);
+        assert_eq!(system.tag('K'), -1.0);
+        assert_eq!(system.imbalance(), 2.0);
+        assert!(!system.has_ace_side_count());
+
+        assert!(CountingSystem::parse("name Short\ntags 1 1 1\n").is_err());
+        assert!(CountingSystem::parse("tags 1 1 1 1 1 0 0 0 -1 -1\n").is_err());
+        assert!(CountingSystem::parse("name X\ntags 1 1 1 1 1 0 0 0 -1 -1\nside-count aces\n").unwrap().has_ace_side_count());
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+
+use crate::game::composition::{Composition, NUM_OF_RANKS, ACE_RANK, TEN_RANK, rank_index, rank_value};
+
+const BLACKJACK        : u8 = 21;
+const DEALER_STANDS_ON : u8 = 17;
+
+pub const NUM_OF_OUTCOMES   : usize = 7;
+pub const BLACKJACK_OUTCOME : usize = 5;
+pub const BUST_OUTCOME      : usize = 6;
+
+/// Partial draws kept between lookups before they are thrown away, which
+/// keeps memory under a hundred megabytes.
+const MAX_DRAWS : usize = 250_000;
+
+const OUTCOME_NAMES : [&str; NUM_OF_OUTCOMES] = ["17", "18", "19", "20", "21", "BJ", "Bust"];
+const UPCARDS       : [char; NUM_OF_RANKS] = ['2', '3', '4', '5', '6', '7', '8', '9', 'T', 'A'];
+
+/// Probability of each way the dealer's hand can finish: a total from 17
+/// through 21, a blackjack, or a bust.
+pub type DealerDistribution = [f64; NUM_OF_OUTCOMES];
+
+/// Index into a `DealerDistribution` for a final total of 17 through 21.
+pub fn total_outcome(total : u8) -> usize {
+    return (total - DEALER_STANDS_ON) as usize;
+}
+
+/// Exact dealer outcome probabilities, cached by shoe composition so that
+/// repeated queries during a shoe are only worked out once. The partial
+/// draws behind them are kept too, since the compositions met while
+/// evaluating one hand differ by only a few cards and share most of them.
+#[derive(Debug, Default)]
+pub struct DealerOdds {
+    cache : HashMap<(Composition, usize, bool, bool), DealerDistribution>,
+    draws : DrawCache,
+}
+
+impl DealerOdds {
+    pub fn new() -> DealerOdds {
+        DealerOdds {
+            cache : HashMap::new(),
+            draws : HashMap::new(),
+        }
+    }
+
+    /// Outcome probabilities for `upcard` with the hole card and every hit
+    /// drawn from `composition`, which must not include the upcard.
+    pub fn distribution(&mut self, upcard : char, composition : &Composition, dealer_hits_soft_17 : bool) -> DealerDistribution {
+        return self.lookup(rank_index(upcard), composition, dealer_hits_soft_17, false);
+    }
+
+    /// Outcome probabilities once the dealer has peeked and found no
+    /// blackjack, which is what the player faces when making a decision.
+    pub fn distribution_after_peek(&mut self, upcard : char, composition : &Composition, dealer_hits_soft_17 : bool) -> DealerDistribution {
+        return self.lookup(rank_index(upcard), composition, dealer_hits_soft_17, true);
+    }
+
+    /// Distributions for every upcard from 2 through ace, each with the
+    /// upcard itself taken out of `composition`.
+    pub fn table(&mut self, composition : &Composition, dealer_hits_soft_17 : bool) -> Vec<(char, DealerDistribution)> {
+        let mut table: Vec<(char, DealerDistribution)> = Vec::new();
+        for upcard in UPCARDS {
+            let rank: usize = rank_index(upcard);
+            if composition.count(rank) == 0 {
+                continue;
+            }
+            let mut remaining: Composition = composition.clone();
+            remaining.remove(rank);
+            table.push((upcard, self.distribution(upcard, &remaining, dealer_hits_soft_17)));
+        }
+        return table;
+    }
+
+    pub fn display_table(&mut self, composition : &Composition, dealer_hits_soft_17 : bool) {
+        println!("Dealer {} soft 17, {} cards in the shoe", if dealer_hits_soft_17 { "hits" } else { "stands on" }, composition.total());
+
+        let mut header: String = "Upcard".to_string();
+        for name in OUTCOME_NAMES {
+            header += &format!(" {:>7}", name);
+        }
+        println!("{}", header);
+
+        for (upcard, distribution) in self.table(composition, dealer_hits_soft_17) {
+            let mut row: String = format!("{:<6}", upcard);
+            for probability in distribution {
+                row += &format!(" {:>6.2}%", probability * 100.0);
+            }
+            println!("{}", row);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.draws.clear();
+    }
+
+    fn lookup(&mut self, upcard : usize, composition : &Composition, dealer_hits_soft_17 : bool, peeked : bool) -> DealerDistribution {
+        let key: (Composition, usize, bool, bool) = (composition.clone(), upcard, dealer_hits_soft_17, peeked);
+        if let Some(distribution) = self.cache.get(&key) {
+            return *distribution;
+        }
+
+        if self.draws.len() > MAX_DRAWS {
+            self.draws.clear();
+        }
+
+        // After the peek the hole card can't be the one that completes a blackjack.
+        let excluded_rank: Option<usize> = match (peeked, upcard) {
+            (true, ACE_RANK) => Some(TEN_RANK),
+            (true, TEN_RANK) => Some(ACE_RANK),
+            _=> None,
+        };
+
+        let distribution: DealerDistribution = dealer_draw(&mut composition.clone(),
+                                                           rank_value(upcard),
+                                                           upcard == ACE_RANK,
+                                                           1,
+                                                           excluded_rank,
+                                                           dealer_hits_soft_17,
+                                                           &mut self.draws);
+        self.cache.insert(key, distribution);
+        return distribution;
+    }
+}
+
+/// Hands drawn in a different order reach the same state, so partial results
+/// are memoised by the cards left, the dealer's hand so far, the rank the
+/// peek rules out of the hole card and whether the dealer hits soft 17.
+type DrawCache = HashMap<(Composition, u8, bool, u8, Option<usize>, bool), DealerDistribution>;
+
+fn dealer_draw(composition : &mut Composition,
+               hard_total : u8,
+               has_ace : bool,
+               num_of_cards : u8,
+               excluded_rank : Option<usize>,
+               dealer_hits_soft_17 : bool,
+               draw_cache : &mut DrawCache) -> DealerDistribution {
+    let mut distribution: DealerDistribution = [0.0; NUM_OF_OUTCOMES];
+
+    let soft: bool = has_ace && hard_total + 10 <= BLACKJACK;
+    let total: u8 = if soft { hard_total + 10 } else { hard_total };
+    if total > BLACKJACK {
+        distribution[BUST_OUTCOME] = 1.0;
+        return distribution;
+    }
+    if total == BLACKJACK && num_of_cards == 2 {
+        distribution[BLACKJACK_OUTCOME] = 1.0;
+        return distribution;
+    }
+    if total > DEALER_STANDS_ON || (total == DEALER_STANDS_ON && !(soft && dealer_hits_soft_17)) {
+        distribution[total_outcome(total)] = 1.0;
+        return distribution;
+    }
+
+    let key: (Composition, u8, bool, u8, Option<usize>, bool) = (composition.clone(), hard_total, has_ace, num_of_cards.min(3), excluded_rank, dealer_hits_soft_17);
+    if let Some(cached) = draw_cache.get(&key) {
+        return *cached;
+    }
+
+    let excluded_count: u16 = excluded_rank.map_or(0, |rank| composition.count(rank));
+    let drawable: u16 = composition.total() - excluded_count;
+    if drawable == 0 {
+        return distribution;
+    }
+
+    for rank in 0..NUM_OF_RANKS {
+        if Some(rank) == excluded_rank || composition.count(rank) == 0 {
+            continue;
+        }
+        let probability: f64 = composition.count(rank) as f64 / drawable as f64;
+
+        composition.remove(rank);
+        let outcome: DealerDistribution = dealer_draw(composition,
+                                                      hard_total + rank_value(rank),
+                                                      has_ace || rank == ACE_RANK,
+                                                      num_of_cards + 1,
+                                                      None,
+                                                      dealer_hits_soft_17,
+                                                      draw_cache);
+        composition.add(rank);
+
+        for (total, outcome_probability) in distribution.iter_mut().zip(outcome.iter()) {
+            *total += probability * outcome_probability;
+        }
+    }
+
+    draw_cache.insert(key, distribution);
+    return distribution;
+}
+
+
+#[cfg(test)]
+mod dealer_tests {
+    use super::*;
+
+    #[test]
+    fn dealer_distribution_sums_to_one() {
+        let mut dealer_odds = DealerOdds::new();
+        for (_upcard, distribution) in dealer_odds.table(&Composition::full_shoe(1), false) {
+            let sum: f64 = distribution.iter().sum();
+            assert!((sum - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn dealer_blackjack_only_with_ace_or_ten() {
+        let mut dealer_odds = DealerOdds::new();
+        let shoe = Composition::full_shoe(6);
+        for (upcard, distribution) in dealer_odds.table(&shoe, false) {
+            if upcard == 'A' || upcard == 'T' {
+                assert!(distribution[BLACKJACK_OUTCOME] > 0.0);
+            }
+            else {
+                assert_eq!(distribution[BLACKJACK_OUTCOME], 0.0);
+            }
+        }
+
+        let mut remaining = shoe.clone();
+        remaining.remove(ACE_RANK);
+        let distribution = dealer_odds.distribution_after_peek('A', &remaining, false);
+        assert_eq!(distribution[BLACKJACK_OUTCOME], 0.0);
+    }
+
+    #[test]
+    fn dealer_six_deck_bust_rates() {
+        // Well known six deck bust rates: about 42% against a 6 and 11.5% against an ace under S17.
+        let mut dealer_odds = DealerOdds::new();
+        let mut remaining = Composition::full_shoe(6);
+        remaining.remove(rank_index('6'));
+        let six = dealer_odds.distribution('6', &remaining, false);
+        assert!((six[BUST_OUTCOME] - 0.42).abs() < 0.005);
+
+        let mut remaining = Composition::full_shoe(6);
+        remaining.remove(ACE_RANK);
+        let ace = dealer_odds.distribution('A', &remaining, false);
+        assert!((ace[BUST_OUTCOME] - 0.115).abs() < 0.005);
+    }
+
+    #[test]
+    fn dealer_hitting_soft_17_changes_outcomes() {
+        let mut dealer_odds = DealerOdds::new();
+        let mut remaining = Composition::full_shoe(6);
+        remaining.remove(rank_index('6'));
+        let stands = dealer_odds.distribution('6', &remaining, false);
+        let hits = dealer_odds.distribution('6', &remaining, true);
+        assert!(hits[total_outcome(17)] < stands[total_outcome(17)]);
+        assert!(hits[BUST_OUTCOME] > stands[BUST_OUTCOME]);
+    }
+}
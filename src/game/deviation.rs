@@ -0,0 +1,241 @@
+use crate::game::chart::{self, ChartAction, Section, UPCARDS};
+
+/// The standard Hi-Lo insurance index, for when no index table is loaded.
+pub const HI_LO_INSURANCE : Condition = Condition::AtLeast(3.0);
+
+/// Hi-Lo indices for the Illustrious 18 and the Fab 4 surrenders, four to
+/// eight decks, dealer stands on soft 17. Each line gives the hand, the
+/// upcard, the play and the true counts it applies at.
+pub const ILLUSTRIOUS_18_FAB_4 : &str = "
+insurance >=3
+
+# Illustrious 18
+16 T Rs >=0
+15 T Rs >=4
+TT 5 P  >=5
+TT 6 P  >=4
+10 T D  >=4
+12 3 S  >=2
+12 2 S  >=3
+11 A D  >=1
+9  2 D  >=1
+10 A D  >=4
+9  7 D  >=3
+16 9 Rs >=5
+13 2 H  <-1
+12 4 H  <0
+12 5 H  <-2
+12 6 H  <-1
+13 3 H  <-2
+
+# Fab 4
+14 T Rh >=3
+15 T H  <0
+15 9 Rh >=2
+15 A Rh >=1
+";
+
+/// True counts at which an index play applies.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition {
+    AtLeast(f64),
+    Below(f64),
+}
+
+impl Condition {
+    pub fn matches(&self, true_count : f64) -> bool {
+        match self {
+            Condition::AtLeast(index) => return true_count >= *index,
+            Condition::Below(index)   => return true_count < *index,
+        }
+    }
+
+    /// The true count the play changes at.
+    pub fn index(&self) -> f64 {
+        match self {
+            Condition::AtLeast(index) | Condition::Below(index) => return *index,
+        }
+    }
+
+    /// Reads `>=N` or `<N`.
+    pub fn parse(text : &str) -> Option<Condition> {
+        if let Some(index) = text.strip_prefix(">=") {
+            return index.parse().ok().map(Condition::AtLeast);
+        }
+        if let Some(index) = text.strip_prefix('<') {
+            return index.parse().ok().map(Condition::Below);
+        }
+        return None;
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Condition::AtLeast(index) => return format!("true count {:+} or more", index),
+            Condition::Below(index)   => return format!("true count below {:+}", index),
+        }
+    }
+}
+
+/// A chart cell played differently at some true counts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Deviation {
+    pub section   : Section,
+    pub row       : usize,
+    pub column    : usize,
+    pub action    : ChartAction,
+    pub condition : Condition,
+}
+
+impl Deviation {
+    pub fn describe(&self) -> String {
+        return format!("{} vs {}: {} at {}",
+                       chart::row_label(self.section, self.row),
+                       UPCARDS[self.column],
+                       self.action.code(),
+                       self.condition.describe());
+    }
+}
+
+/// Index plays layered over a strategy chart.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndexTable {
+    deviations : Vec<Deviation>,
+    insurance  : Option<Condition>,
+}
+
+impl IndexTable {
+    pub fn new() -> IndexTable {
+        IndexTable {
+            deviations : Vec::new(),
+            insurance : None,
+        }
+    }
+
+    pub fn illustrious_18_fab_4() -> IndexTable {
+        return IndexTable::parse(ILLUSTRIOUS_18_FAB_4).unwrap();
+    }
+
+    /// Parses lines of `hand upcard play condition`, e.g. `16 T S >=0`, and
+    /// an optional `insurance condition` line.
+    pub fn parse(text : &str) -> Result<IndexTable, String> {
+        let mut table: IndexTable = IndexTable::new();
+
+        for line in text.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.is_empty() || fields[0].starts_with('#') {
+                continue;
+            }
+
+            if fields[0] == "insurance" {
+                let condition: &str = fields.get(1).ok_or("insurance needs a condition")?;
+                table.insurance = Some(Condition::parse(condition).ok_or(format!("unknown condition '{}'", condition))?);
+                continue;
+            }
+
+            if fields.len() != 4 {
+                return Err(format!("index line '{}' should be hand, upcard, play and condition", line.trim()));
+            }
+            let (section, row) = chart::label_cell(fields[0]).ok_or(format!("unknown hand '{}'", fields[0]))?;
+            let upcard: char = fields[1].chars().next().filter(|upcard| fields[1].len() == 1 && UPCARDS.contains(upcard))
+                                                       .ok_or(format!("unknown upcard '{}'", fields[1]))?;
+            let action: ChartAction = ChartAction::from_code(fields[2]).ok_or(format!("unknown action '{}'", fields[2]))?;
+            let condition: Condition = Condition::parse(fields[3]).ok_or(format!("unknown condition '{}'", fields[3]))?;
+            table.deviations.push(Deviation {
+                section,
+                row,
+                column : chart::upcard_column(upcard),
+                action,
+                condition,
+            });
+        }
+        return Ok(table);
+    }
+
+    /// Every index play for this hand, whatever the count.
+    pub fn deviations_for(&self, player_cards : &[char], upcard : char, can_split : bool) -> Vec<&Deviation> {
+        let cell: Option<(Section, usize, usize)> = chart::cell(player_cards, can_split).map(|(section, row)| (section, row, chart::upcard_column(upcard)));
+        return self.deviations.iter().filter(|deviation| Some((deviation.section, deviation.row, deviation.column)) == cell).collect();
+    }
+
+    /// The index play for this hand at `true_count`, if any. When several
+    /// apply the one furthest from the basic play wins: the highest `>=`
+    /// index, otherwise the lowest `<` index.
+    pub fn lookup(&self, player_cards : &[char], upcard : char, can_split : bool, true_count : f64) -> Option<&Deviation> {
+        let mut best: Option<&Deviation> = None;
+        for deviation in self.deviations_for(player_cards, upcard, can_split) {
+            if !deviation.condition.matches(true_count) {
+                continue;
+            }
+            best = match (best.map(|best| best.condition), deviation.condition) {
+                (None, _) => Some(deviation),
+                (Some(Condition::Below(_)), Condition::AtLeast(_)) => Some(deviation),
+                (Some(Condition::AtLeast(current)), Condition::AtLeast(index)) if index > current => Some(deviation),
+                (Some(Condition::Below(current)), Condition::Below(index)) if index < current => Some(deviation),
+                _=> best,
+            };
+        }
+        return best;
+    }
+
+    pub fn deviations(&self) -> &[Deviation] {
+        return &self.deviations;
+    }
+
+    pub fn take_insurance(&self, true_count : f64) -> bool {
+        return self.insurance.is_some_and(|condition| condition.matches(true_count));
+    }
+
+    pub fn insurance(&self) -> Option<Condition> {
+        return self.insurance;
+    }
+}
+
+
+#[cfg(test)]
+mod deviation_tests {
+    use super::*;
+
+    #[test]
+    fn illustrious_18_lookup() {
+        let table = IndexTable::illustrious_18_fab_4();
+
+        let deviation = table.lookup(&['T', '6'], 'T', false, 0.0).unwrap();
+        assert_eq!(deviation.action, ChartAction::SurrenderOrStand);
+        assert!(table.lookup(&['T', '6'], 'T', false, -0.5).is_none());
+
+        assert_eq!(table.lookup(&['T', '2'], '3', false, 2.4).unwrap().action, ChartAction::Stand);
+        assert!(table.lookup(&['T', '2'], '3', false, 1.9).is_none());
+        assert_eq!(table.lookup(&['T', '3'], '2', false, -1.5).unwrap().action, ChartAction::Hit);
+        assert_eq!(table.lookup(&['T', 'T'], '6', true, 4.0).unwrap().action, ChartAction::Split);
+        assert!(table.lookup(&['T', 'T'], '6', false, 4.0).is_none());
+
+        assert_eq!(table.deviations_for(&['T', '5'], 'T', false).len(), 2);
+        assert!(table.deviations_for(&['T', '7'], 'T', false).is_empty());
+    }
+
+    #[test]
+    fn highest_matching_index_wins() {
+        let table = IndexTable::illustrious_18_fab_4();
+        assert_eq!(table.lookup(&['T', '5'], 'T', false, -1.0).unwrap().action, ChartAction::Hit);
+        assert!(table.lookup(&['T', '5'], 'T', false, 1.0).is_none());
+        assert_eq!(table.lookup(&['T', '5'], 'T', false, 4.5).unwrap().action, ChartAction::SurrenderOrStand);
+    }
+
+    #[test]
+    fn insurance_index() {
+        let table = IndexTable::illustrious_18_fab_4();
+        assert!(table.take_insurance(3.0));
+        assert!(!table.take_insurance(2.9));
+        assert!(!IndexTable::new().take_insurance(10.0));
+    }
+
+    #[test]
+    fn parse_index_table_errors() {
+        assert!(IndexTable::parse("16 T S >=0\n").is_ok());
+        assert!(IndexTable::parse("21 T S >=0\n").is_err());
+        assert!(IndexTable::parse("16 X S >=0\n").is_err());
+        assert!(IndexTable::parse("16 T Q >=0\n").is_err());
+        assert!(IndexTable::parse("16 T S =0\n").is_err());
+        assert!(IndexTable::parse("insurance\n").is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use rand::{thread_rng, Rng};
+
+use crate::game::quiz::QuizScore;
+
+/// Table talk that interrupts play under casino conditions. Most of it is
+/// a question with a right answer; the rest just has to be dealt with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distraction {
+    BlackjackPayout,
+    BetsOnTable,
+    Decks,
+    Arithmetic(u8, u8),
+    Drink,
+}
+
+impl Distraction {
+    pub fn random() -> Distraction {
+        let mut rng = thread_rng();
+        match rng.gen_range(0..5) {
+            0 => return Distraction::BlackjackPayout,
+            1 => return Distraction::BetsOnTable,
+            2 => return Distraction::Decks,
+            3 => return Distraction::Arithmetic(rng.gen_range(3..13), rng.gen_range(3..13)),
+            _=> return Distraction::Drink,
+        }
+    }
+
+    /// What is said at a table with `bets` out.
+    pub fn question(&self, bets : &[f64]) -> String {
+        match self {
+            Distraction::BlackjackPayout  => return format!("Dealer: Say your first hand is a blackjack, what do I pay on {} units?", bets.first().unwrap_or(&0.0)),
+            Distraction::BetsOnTable      => return "Dealer: How many units do you have out right now?".to_string(),
+            Distraction::Decks            => return "Pit boss: How many decks are in this shoe?".to_string(),
+            Distraction::Arithmetic(a, b) => return format!("Player next to you: Quick, what's {} times {}?", a, b),
+            Distraction::Drink            => return "Cocktail waitress: Anything to drink?".to_string(),
+        }
+    }
+
+    /// The right answer at a table with `bets` out on a shoe of
+    /// `num_of_decks`, or `None` when any answer will do.
+    pub fn answer(&self, bets : &[f64], num_of_decks : u8) -> Option<f64> {
+        match self {
+            Distraction::BlackjackPayout  => return Some(1.5 * bets.first().unwrap_or(&0.0)),
+            Distraction::BetsOnTable      => return Some(bets.iter().sum()),
+            Distraction::Decks            => return Some(num_of_decks as f64),
+            Distraction::Arithmetic(a, b) => return Some(*a as f64 * *b as f64),
+            Distraction::Drink            => return None,
+        }
+    }
+}
+
+/// Interrupts play at random and keeps score of the answers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distractions {
+    frequency : f64,
+    score     : QuizScore,
+}
+
+impl Distractions {
+    /// `frequency` is the chance of an interruption each time one could come.
+    pub fn new(frequency : f64) -> Distractions {
+        Distractions {
+            frequency,
+            score : QuizScore::default(),
+        }
+    }
+
+    pub fn should_interrupt(&self) -> bool {
+        return thread_rng().gen_bool(self.frequency);
+    }
+
+    /// Grades `answer` to `distraction`; questions without a right answer aren't scored.
+    pub fn grade(&mut self, distraction : Distraction, answer : Option<f64>, bets : &[f64], num_of_decks : u8) -> Option<bool> {
+        let expected: f64 = distraction.answer(bets, num_of_decks)?;
+        let correct: bool = answer == Some(expected);
+        self.score.record(correct);
+        return Some(correct);
+    }
+
+    pub fn score(&self) -> QuizScore {
+        return self.score;
+    }
+}
+
+
+#[cfg(test)]
+mod distraction_tests {
+    use super::*;
+
+    #[test]
+    fn answers_follow_the_table() {
+        let bets = [4.0, 2.0];
+        assert_eq!(Distraction::BlackjackPayout.answer(&bets, 6), Some(6.0));
+        assert_eq!(Distraction::BetsOnTable.answer(&bets, 6), Some(6.0));
+        assert_eq!(Distraction::Decks.answer(&bets, 8), Some(8.0));
+        assert_eq!(Distraction::Arithmetic(7, 8).answer(&bets, 6), Some(56.0));
+        assert_eq!(Distraction::Drink.answer(&bets, 6), None);
+        assert!(Distraction::BlackjackPayout.question(&bets).contains("4 units"));
+    }
+
+    #[test]
+    fn only_questions_with_answers_are_scored() {
+        let mut distractions = Distractions::new(0.5);
+        assert_eq!(distractions.grade(Distraction::Decks, Some(6.0), &[1.0], 6), Some(true));
+        assert_eq!(distractions.grade(Distraction::BetsOnTable, None, &[1.0], 6), Some(false));
+        assert_eq!(distractions.grade(Distraction::Drink, None, &[1.0], 6), None);
+        assert_eq!(distractions.score(), QuizScore { asked : 2, correct : 1 });
+    }
+}
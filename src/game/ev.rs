@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use crate::game::action::UserAction;
+use crate::game::composition::{Composition, NUM_OF_RANKS, ACE_RANK, TEN_RANK, rank_index, rank_value};
+use crate::game::dealer::{self, DealerDistribution, DealerOdds, BUST_OUTCOME};
+use crate::game::rules::Rules;
+
+const BLACKJACK        : u8 = 21;
+const DEALER_STANDS_ON : u8 = 17;
+const BLACKJACK_PAYOUT : f64 = 1.5;
+const SURRENDER_EV     : f64 = -0.5;
+const INSURANCE_PAYOUT : f64 = 2.0;
+
+/// Expected value, in units of the initial bet, of every action available
+/// to the player. Actions the rules or the hand don't allow are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionEvs {
+    pub hit         : f64,
+    pub stand       : f64,
+    pub double_down : Option<f64>,
+    pub split       : Option<f64>,
+    pub surrender   : Option<f64>,
+}
+
+impl ActionEvs {
+    pub fn ev(&self, action : UserAction) -> Option<f64> {
+        match action {
+            UserAction::Hit        => Some(self.hit),
+            UserAction::Stand      => Some(self.stand),
+            UserAction::DoubleDown => self.double_down,
+            UserAction::Split      => self.split,
+            UserAction::Surrender  => self.surrender,
+            UserAction::Unknown    => None,
+        }
+    }
+
+    pub fn best(&self) -> (UserAction, f64) {
+        return self.best_among(&[UserAction::Hit, UserAction::DoubleDown, UserAction::Split, UserAction::Surrender]);
+    }
+
+    /// Best of standing and the available `actions`.
+    pub fn best_among(&self, actions : &[UserAction]) -> (UserAction, f64) {
+        let mut best: (UserAction, f64) = (UserAction::Stand, self.stand);
+        for action in actions {
+            if let Some(ev) = self.ev(*action) {
+                if ev > best.1 {
+                    best = (*action, ev);
+                }
+            }
+        }
+        return best;
+    }
+
+    /// Drops the EV of any action not in `actions`.
+    pub fn restrict(&mut self, actions : &[UserAction]) {
+        if !actions.contains(&UserAction::DoubleDown) {
+            self.double_down = None;
+        }
+        if !actions.contains(&UserAction::Split) {
+            self.split = None;
+        }
+        if !actions.contains(&UserAction::Surrender) {
+            self.surrender = None;
+        }
+    }
+
+    /// How much EV is given up by choosing `action` over the best action.
+    pub fn cost(&self, action : UserAction) -> Option<f64> {
+        let best_ev: f64 = self.best().1;
+        return self.ev(action).map(|ev| best_ev - ev);
+    }
+
+    pub fn display(&self) {
+        let mut line: String = format!("EV Hit: {:+.4}  Stand: {:+.4}", self.hit, self.stand);
+        if let Some(ev) = self.double_down {
+            line += &format!("  Double Down: {:+.4}", ev);
+        }
+        if let Some(ev) = self.split {
+            line += &format!("  Split: {:+.4}", ev);
+        }
+        if let Some(ev) = self.surrender {
+            line += &format!("  Surrender: {:+.4}", ev);
+        }
+        println!("{}", line);
+    }
+}
+
+/// Computes the exact expected value of each action for `player_cards`
+/// against `upcard`, drawing from the unseen cards in `composition`
+/// (which must not include the player's cards or the upcard).
+/// `split_hand` says the hand came from a split, so a two card 21 is not
+/// a blackjack and the hand may not be split again.
+///
+/// The dealer is assumed to have already checked for blackjack. Split
+/// hands are played out independently and may not be resplit.
+pub fn evaluate(player_cards : &[char],
+                split_hand : bool,
+                upcard : char,
+                composition : &Composition,
+                rules : &Rules,
+                dealer_odds : &mut DealerOdds) -> ActionEvs {
+    let mut evaluation: Evaluation = Evaluation::new(upcard, rules, dealer_odds);
+
+    let mut hard_total: u8 = 0;
+    let mut has_ace: bool = false;
+    for card in player_cards {
+        let rank: usize = rank_index(*card);
+        hard_total += rank_value(rank);
+        has_ace |= rank == ACE_RANK;
+    }
+
+    let first_two_cards: bool = player_cards.len() == 2;
+    let natural: bool = first_two_cards && !split_hand && best_total(hard_total, has_ace) == BLACKJACK;
+
+    let stand: f64 = if natural { BLACKJACK_PAYOUT } else { evaluation.stand_ev(hard_total, has_ace, composition) };
+    let hit: f64 = evaluation.hit_ev(hard_total, has_ace, &mut composition.clone());
+
+    let double_down: Option<f64> = if first_two_cards {
+        Some(evaluation.double_ev(hard_total, has_ace, &mut composition.clone()))
+    } else {
+        None
+    };
+
+    let split: Option<f64> = if first_two_cards && !split_hand && rank_index(player_cards[0]) == rank_index(player_cards[1]) {
+        Some(evaluation.split_ev(rank_index(player_cards[0]), &mut composition.clone()))
+    } else {
+        None
+    };
+
+    let surrender: Option<f64> = if first_two_cards && rules.late_surrender { Some(SURRENDER_EV) } else { None };
+
+    return ActionEvs {
+        hit,
+        stand,
+        double_down,
+        split,
+        surrender,
+    };
+}
+
+struct Evaluation<'a> {
+    upcard      : char,
+    rules       : &'a Rules,
+    dealer_odds : &'a mut DealerOdds,
+    hit_cache   : HashMap<(Composition, u8, bool), f64>,
+}
+
+impl<'a> Evaluation<'a> {
+    fn new(upcard : char, rules : &'a Rules, dealer_odds : &'a mut DealerOdds) -> Evaluation<'a> {
+        Evaluation {
+            upcard,
+            rules,
+            dealer_odds,
+            hit_cache : HashMap::new(),
+        }
+    }
+
+    fn stand_ev(&mut self, hard_total : u8, has_ace : bool, composition : &Composition) -> f64 {
+        let player_total: u8 = best_total(hard_total, has_ace);
+        if player_total > BLACKJACK {
+            return -1.0;
+        }
+
+        let distribution: DealerDistribution = self.dealer_odds.distribution_after_peek(self.upcard, composition, self.rules.dealer_hits_soft_17);
+        let mut ev: f64 = distribution[BUST_OUTCOME];
+        for dealer_total in DEALER_STANDS_ON..=BLACKJACK {
+            let probability: f64 = distribution[dealer::total_outcome(dealer_total)];
+            if player_total > dealer_total {
+                ev += probability;
+            }
+            else if player_total < dealer_total {
+                ev -= probability;
+            }
+        }
+        return ev;
+    }
+
+    /// EV of taking one card and then continuing to hit or stand optimally.
+    fn hit_ev(&mut self, hard_total : u8, has_ace : bool, composition : &mut Composition) -> f64 {
+        let key: (Composition, u8, bool) = (composition.clone(), hard_total, has_ace);
+        if let Some(ev) = self.hit_cache.get(&key) {
+            return *ev;
+        }
+
+        let mut ev: f64 = 0.0;
+        for rank in 0..NUM_OF_RANKS {
+            let probability: f64 = composition.probability(rank);
+            if probability == 0.0 {
+                continue;
+            }
+
+            let new_total: u8 = hard_total + rank_value(rank);
+            let new_has_ace: bool = has_ace || rank == ACE_RANK;
+            if new_total > BLACKJACK {
+                ev -= probability;
+                continue;
+            }
+
+            composition.remove(rank);
+            let stand: f64 = self.stand_ev(new_total, new_has_ace, composition);
+            let hit: f64 = if best_total(new_total, new_has_ace) < BLACKJACK {
+                self.hit_ev(new_total, new_has_ace, composition)
+            } else {
+                stand
+            };
+            composition.add(rank);
+
+            ev += probability * stand.max(hit);
+        }
+
+        self.hit_cache.insert(key, ev);
+        return ev;
+    }
+
+    /// EV of doubling the bet and taking exactly one more card.
+    fn double_ev(&mut self, hard_total : u8, has_ace : bool, composition : &mut Composition) -> f64 {
+        let mut ev: f64 = 0.0;
+        for rank in 0..NUM_OF_RANKS {
+            let probability: f64 = composition.probability(rank);
+            if probability == 0.0 {
+                continue;
+            }
+
+            composition.remove(rank);
+            ev += probability * self.stand_ev(hard_total + rank_value(rank), has_ace || rank == ACE_RANK, composition);
+            composition.add(rank);
+        }
+        return 2.0 * ev;
+    }
+
+    fn split_ev(&mut self, pair_rank : usize, composition : &mut Composition) -> f64 {
+        let mut ev: f64 = 0.0;
+        for rank in 0..NUM_OF_RANKS {
+            let probability: f64 = composition.probability(rank);
+            if probability == 0.0 {
+                continue;
+            }
+
+            let hard_total: u8 = rank_value(pair_rank) + rank_value(rank);
+            let has_ace: bool = pair_rank == ACE_RANK || rank == ACE_RANK;
+
+            composition.remove(rank);
+            let stand: f64 = self.stand_ev(hard_total, has_ace, composition);
+            let hand_ev: f64 = if pair_rank == ACE_RANK {
+                // Split aces receive a single card each.
+                stand
+            } else {
+                let mut best: f64 = stand.max(self.hit_ev(hard_total, has_ace, composition));
+                if self.rules.double_after_split {
+                    best = best.max(self.double_ev(hard_total, has_ace, composition));
+                }
+                best
+            };
+            composition.add(rank);
+
+            ev += probability * hand_ev;
+        }
+        return 2.0 * ev;
+    }
+}
+
+/// Expected value of an insurance bet per unit insured, with the dealer's
+/// hole card drawn from the unseen cards in `composition`. It pays 2 to 1
+/// when the hole card is a ten, so it is worth taking once more than a
+/// third of the unseen cards are tens.
+pub fn insurance_ev(composition : &Composition) -> f64 {
+    let ten_probability: f64 = composition.probability(TEN_RANK);
+    return INSURANCE_PAYOUT * ten_probability - (1.0 - ten_probability);
+}
+
+fn best_total(hard_total : u8, has_ace : bool) -> u8 {
+    if has_ace && hard_total + 10 <= BLACKJACK {
+        return hard_total + 10;
+    }
+    return hard_total;
+}
+
+
+#[cfg(test)]
+mod ev_tests {
+    use super::*;
+    use crate::game::shoe::SUIT;
+
+    #[test]
+    fn insurance_ev_follows_ten_density() {
+        assert!((insurance_ev(&Composition::infinite_deck()) + 1.0 / 13.0).abs() < 1e-12);
+        assert!((insurance_ev(&Composition::from_cards(&['T', 'K', '5'])) - 1.0).abs() < 1e-12);
+        assert!(insurance_ev(&Composition::from_cards(&['T', '4', '5'])).abs() < 1e-12);
+        assert!(insurance_ev(&Composition::from_cards(&['A', '4', '5'])) < 0.0);
+    }
+
+    fn single_deck_without(cards : &[char]) -> Composition {
+        let mut composition = Composition::new();
+        for _suit in 0..4 {
+            for card in SUIT {
+                composition.add(rank_index(card));
+            }
+        }
+        for card in cards {
+            composition.remove(rank_index(*card));
+        }
+        return composition;
+    }
+
+    #[test]
+    fn ev_stand_on_twenty_beats_hitting() {
+        let rules = Rules::default();
+        let composition = single_deck_without(&['T', 'K', '6']);
+        let evs = evaluate(&['T', 'K'], false, '6', &composition, &rules, &mut DealerOdds::new());
+        assert!(evs.stand > 0.5);
+        assert_eq!(evs.best().0, UserAction::Stand);
+        assert!(evs.cost(UserAction::Hit).unwrap() > 0.5);
+        assert!(evs.split.is_some());
+    }
+
+    #[test]
+    fn ev_double_eleven_against_six() {
+        let rules = Rules::default();
+        let composition = single_deck_without(&['6', '5', '6']);
+        let evs = evaluate(&['6', '5'], false, '6', &composition, &rules, &mut DealerOdds::new());
+        assert_eq!(evs.best().0, UserAction::DoubleDown);
+        assert!(evs.double_down.unwrap() > evs.hit);
+    }
+
+    #[test]
+    fn ev_surrender_sixteen_against_ten() {
+        let rules = Rules::default();
+        let composition = single_deck_without(&['T', '6', 'T']);
+        let evs = evaluate(&['T', '6'], false, 'T', &composition, &rules, &mut DealerOdds::new());
+        assert_eq!(evs.surrender, Some(SURRENDER_EV));
+        assert!(evs.hit < SURRENDER_EV);
+        assert!(evs.stand < SURRENDER_EV);
+
+        let no_surrender = Rules { late_surrender : false, ..Rules::default() };
+        let evs = evaluate(&['T', '6'], false, 'T', &composition, &no_surrender, &mut DealerOdds::new());
+        assert_eq!(evs.surrender, None);
+    }
+
+    #[test]
+    fn ev_natural_pays_three_to_two() {
+        let rules = Rules::default();
+        let composition = single_deck_without(&['A', 'K', '9']);
+        let evs = evaluate(&['A', 'K'], false, '9', &composition, &rules, &mut DealerOdds::new());
+        assert_eq!(evs.stand, BLACKJACK_PAYOUT);
+    }
+
+    #[test]
+    fn ev_split_twenty_one_is_not_a_natural() {
+        let rules = Rules::default();
+        let composition = single_deck_without(&['A', 'K', 'K', '9']);
+        let evs = evaluate(&['A', 'K'], true, '9', &composition, &rules, &mut DealerOdds::new());
+        assert!(evs.stand < 1.0 && evs.stand > 0.5);
+        assert_eq!(evs.split, None);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::game::action::UserAction;
+use crate::game::composition::rank_index;
+use crate::game::hand::Hand;
+
+/// Plural names for the cards of a pair.
+fn pair_name(card : char) -> String {
+    match card {
+        '2'                   => return "twos".to_string(),
+        '3'                   => return "threes".to_string(),
+        '4'                   => return "fours".to_string(),
+        '5'                   => return "fives".to_string(),
+        '6'                   => return "sixes".to_string(),
+        '7'                   => return "sevens".to_string(),
+        '8'                   => return "eights".to_string(),
+        '9'                   => return "nines".to_string(),
+        'T' | 'J' | 'Q' | 'K' => return "tens".to_string(),
+        _=> return "aces".to_string(),
+    }
+}
+
+/// A dealer bust card: the dealer has to hit a stiff hand more often under these.
+fn is_bust_card(upcard : char) -> bool {
+    return matches!(upcard, '2'..='6');
+}
+
+/// The rule of thumb behind playing `player_cards` against `upcard` with
+/// `action`, the basic strategy play for the hand.
+pub fn rule_of_thumb(player_cards : &[char], upcard : char, can_split : bool, action : UserAction) -> String {
+    let mut hand: Hand = Hand::new(Vec::new());
+    for card in player_cards {
+        hand.add_card(*card);
+    }
+    let total: u8 = hand.total();
+    let soft: bool = hand.is_soft();
+    let pair: Option<char> = if can_split && hand.is_pair() { Some(player_cards[0]) } else { None };
+    let pair_rank: Option<usize> = pair.map(rank_index);
+    let bust_card: bool = is_bust_card(upcard);
+
+    match action {
+        UserAction::Surrender => return format!("Surrender {} against {}: it loses well over half the time however it is played, so taking back half the bet is the cheapest way out",
+                                                if soft { format!("soft {}", total) } else { format!("hard {}", total) },
+                                                upcard),
+        UserAction::Split if pair_rank == Some(rank_index('A')) => return "Always split aces: each one starts a hand of 11 instead of one soft 12".to_string(),
+        UserAction::Split if pair_rank == Some(rank_index('8')) => return "Always split eights: 16 is the worst total there is, while each 8 starts a fair hand".to_string(),
+        UserAction::Split if bust_card => return format!("Split {} against a dealer bust card (2-6): more money goes out when the dealer is most likely to bust", pair_name(pair.unwrap_or(upcard))),
+        UserAction::Split => return format!("Split {} against {}: two hands starting from one card each do better here than the pair played together", pair_name(pair.unwrap_or(upcard)), upcard),
+        UserAction::DoubleDown if soft => return format!("Double soft {} against a dealer bust card (2-6): one card can't bust it, and the dealer busts often", total),
+        UserAction::DoubleDown => return format!("Double {} against {}: a ten is the likeliest next card, and one card makes a strong hand while the dealer is weaker", total, upcard),
+        UserAction::Stand if pair_rank == Some(rank_index('T')) => return "Never split tens: 20 already wins most hands".to_string(),
+        UserAction::Stand if !soft && total >= 17 => return "Always stand on hard 17 or more: a hit busts more often than it helps".to_string(),
+        UserAction::Stand if !soft && bust_card => return "Stand on stiff hands (12-16) against dealer bust cards (2-6): let the dealer take the risk of busting".to_string(),
+        UserAction::Stand if soft && total >= 19 => return "Stand on soft 19 or more: it already beats most dealer hands".to_string(),
+        UserAction::Stand => return format!("Stand on {} {} against {}: it already beats or ties what the dealer usually makes", if soft { "soft" } else { "hard" }, total, upcard),
+        UserAction::Hit if pair_rank == Some(rank_index('5')) => return "Never split fives: together they make 10, which is played as 10".to_string(),
+        UserAction::Hit if !soft && total <= 11 => return "Always hit hard 11 or less: no card can bust it".to_string(),
+        UserAction::Hit if soft => return format!("Hit soft {} against {}: one card can't bust it, and {} isn't enough to stand on here", total, upcard, total),
+        UserAction::Hit if bust_card && total == 12 => return format!("Hit 12 against {}: only tens bust it, and the dealer busts less often under a 2 or 3 than under 4, 5 or 6", upcard),
+        UserAction::Hit if bust_card => return format!("Hit {} against {}: the dealer busts too rarely under a {} for standing to pay", total, upcard, upcard),
+        UserAction::Hit if total <= 16 => return "Hit stiff hands (12-16) against a strong upcard (7-A): the dealer usually makes 17 or more, so standing loses more".to_string(),
+        UserAction::Hit => return format!("Hit {} against {}: the chart expects it to beat standing here", total, upcard),
+        UserAction::Unknown => return String::new(),
+    }
+}
+
+
+#[cfg(test)]
+mod explain_tests {
+    use super::*;
+
+    #[test]
+    fn rules_of_thumb() {
+        assert!(rule_of_thumb(&['T', '6'], '5', false, UserAction::Stand).starts_with("Stand on stiff hands"));
+        assert!(rule_of_thumb(&['T', '6'], '9', false, UserAction::Hit).starts_with("Hit stiff hands"));
+        assert!(rule_of_thumb(&['T', '2'], '3', false, UserAction::Hit).starts_with("Hit 12 against 3"));
+        assert!(rule_of_thumb(&['T', '6'], 'T', false, UserAction::Surrender).starts_with("Surrender hard 16 against T"));
+        assert!(rule_of_thumb(&['8', '8'], 'T', true, UserAction::Split).starts_with("Always split eights"));
+        assert!(rule_of_thumb(&['6', '6'], '4', true, UserAction::Split).starts_with("Split sixes against a dealer bust card"));
+        assert!(rule_of_thumb(&['K', 'Q'], '6', true, UserAction::Stand).starts_with("Never split tens"));
+        assert!(rule_of_thumb(&['5', '5'], 'T', true, UserAction::Hit).starts_with("Never split fives"));
+        assert!(rule_of_thumb(&['A', '7'], '4', false, UserAction::DoubleDown).starts_with("Double soft 18"));
+        assert!(rule_of_thumb(&['A', '7'], '9', false, UserAction::Hit).starts_with("Hit soft 18 against 9"));
+        assert!(rule_of_thumb(&['3', '4', '2'], 'A', false, UserAction::Hit).starts_with("Always hit hard 11 or less"));
+        assert!(rule_of_thumb(&['T', '3'], '2', false, UserAction::Hit).starts_with("Hit 13 against 2: the dealer busts too rarely"));
+        assert!(rule_of_thumb(&['9', '9'], '7', true, UserAction::Split).starts_with("Split nines against 7"));
+        assert!(rule_of_thumb(&['2', '2'], '3', true, UserAction::Split).starts_with("Split twos against a dealer bust card"));
+    }
+}
@@ -1,27 +1,29 @@
-
+use crate::game::composition::rank_index;
 
 const BLACKJACK      : u8 = 21;
 
 #[derive(Debug)]
 pub struct Hand {
-    cards : Vec<char>, 
-    count_a : u8,
-    count_b : u8,
+    pub cards : Vec<char>,
+    pub count_a : u8,
+    pub count_b : u8,
+    pub split : bool,
 }
 
 impl Hand {
     pub fn new(cards : Vec<char>) -> Hand {
         Hand {
-            cards : cards,
+            cards,
             count_a : 0,
             count_b : 0,
+            split : false,
         }
     }
     
     pub fn add_card(&mut self, card : char) -> bool {
         self.cards.push(card);
         self.update_count(card);
-        return self.is_busted() == false;
+        return !self.is_busted();
     }
 
     fn update_count(&mut self, card : char) {
@@ -47,10 +49,49 @@ impl Hand {
         return false;
     }
 
+    /// Best total of the hand, counting an ace as eleven when that doesn't bust.
+    pub fn total(&self) -> u8 {
+        if self.is_soft() {
+            return self.count_a + 10;
+        }
+        return self.count_a;
+    }
+
+    /// A hand is soft when it holds an ace that is being counted as eleven.
+    pub fn is_soft(&self) -> bool {
+        return self.count_b != self.count_a && self.count_a + 10 <= BLACKJACK;
+    }
+
+    /// Twenty-one with the first two cards. A split hand that reaches 21
+    /// with two cards is not a natural, which the caller has to check.
+    pub fn is_natural(&self) -> bool {
+        return self.cards.len() == 2 && self.total() == BLACKJACK;
+    }
+
+    pub fn is_pair(&self) -> bool {
+        return self.cards.len() == 2 && rank_index(self.cards[0]) == rank_index(self.cards[1]);
+    }
+
+    /// Splits a pair into two hands of one card each, returning the second
+    /// hand. Both are marked as split until the next reset.
+    pub fn split(&mut self) -> Hand {
+        let second_card: char = self.cards.pop().expect("cannot split an empty hand");
+        let first_card: char = self.cards[0];
+        self.reset();
+        self.add_card(first_card);
+        self.split = true;
+
+        let mut second_hand: Hand = Hand::new(Vec::new());
+        second_hand.add_card(second_card);
+        second_hand.split = true;
+        return second_hand;
+    }
+
     pub fn reset(&mut self) {
         self.cards.clear();
         self.count_a = 0;
         self.count_b = 0;
+        self.split = false;
     }
 
     pub fn display_hand(&mut self) {
@@ -81,7 +122,7 @@ impl Hand {
 }
 
 
-fn construct_top_row(cards: &Vec<char>) -> String {
+fn construct_top_row(cards: &[char]) -> String {
     let mut row_graphic: String= String::new();
     for _card in cards {
         row_graphic += ".------.";
@@ -90,7 +131,7 @@ fn construct_top_row(cards: &Vec<char>) -> String {
     return row_graphic;    
 }
 
-fn construct_upper_value_row(cards: &Vec<char>) -> String {
+fn construct_upper_value_row(cards: &[char]) -> String {
     let mut row_graphic: String= String::new();
     for card in cards {
         row_graphic += "|";
@@ -113,7 +154,7 @@ fn construct_lower_heart() -> String { return "| :\\/: |".to_string(); }
 fn construct_upper_spade() -> String { return "| :/\\: |".to_string(); }
 fn construct_lower_spade() -> String { return "| (__) |".to_string(); }
 
-fn construct_upper_suit_row(cards: &Vec<char>) -> String {
+fn construct_upper_suit_row(cards: &[char]) -> String {
     let mut row_graphic: String= String::new();
 
     let num_of_cards = cards.len();
@@ -130,7 +171,7 @@ fn construct_upper_suit_row(cards: &Vec<char>) -> String {
     return row_graphic;    
 }
 
-fn construct_lower_suit_row(cards: &Vec<char>) -> String {
+fn construct_lower_suit_row(cards: &[char]) -> String {
     let mut row_graphic: String= String::new();
 
     let num_of_cards = cards.len();
@@ -147,7 +188,7 @@ fn construct_lower_suit_row(cards: &Vec<char>) -> String {
     return row_graphic;    
 }
 
-fn construct_lower_value_row(cards: &Vec<char>) -> String {
+fn construct_lower_value_row(cards: &[char]) -> String {
     let mut row_graphic: String= String::new();
     for card in cards {
         row_graphic += "| '--'";
@@ -158,7 +199,7 @@ fn construct_lower_value_row(cards: &Vec<char>) -> String {
     return row_graphic;    
 }
 
-fn construct_bottom_row(cards: &Vec<char>) -> String {
+fn construct_bottom_row(cards: &[char]) -> String {
     let mut row_graphic: String= String::new();
     for _card in cards {
         row_graphic += "`------'";
@@ -178,26 +219,68 @@ mod hand_tests {
         assert_eq!(hand.cards.len(), 0);
         assert_eq!(hand.count_a, 0);
         assert_eq!(hand.count_b, 0);
-        assert_eq!(hand.is_busted(), false);
+        assert!(!hand.is_busted());
 
-        assert_eq!(hand.add_card('2'), true);
+        assert!(hand.add_card('2'));
         assert_eq!(hand.cards.len(), 1);
         assert_eq!(hand.count_a, 2);
         assert_eq!(hand.count_b, 2);
 
-        assert_eq!(hand.add_card('T'), true);
+        assert!(hand.add_card('T'));
         assert_eq!(hand.cards.len(), 2);
         assert_eq!(hand.count_a, 12);
         assert_eq!(hand.count_b, 12);
 
-        assert_eq!(hand.add_card('A'), true);
+        assert!(hand.add_card('A'));
         assert_eq!(hand.cards.len(), 3);
         assert_eq!(hand.count_a, 13);
         assert_eq!(hand.count_b, 23);
 
-        assert_eq!(hand.add_card('K'), false);
+        assert!(!hand.add_card('K'));
         assert_eq!(hand.cards.len(), 4);
         assert_eq!(hand.count_a, 23);
         assert_eq!(hand.count_b, 33);
     }
+
+    #[test]
+    fn hand_total() {
+        let mut hand = Hand::new(Vec::new());
+        hand.add_card('A');
+        hand.add_card('6');
+        assert_eq!(hand.total(), 17);
+        assert!(hand.is_soft());
+
+        hand.add_card('A');
+        assert_eq!(hand.total(), 18);
+        assert!(hand.is_soft());
+
+        hand.add_card('9');
+        assert_eq!(hand.total(), 17);
+        assert!(!hand.is_soft());
+
+        let mut natural = Hand::new(Vec::new());
+        natural.add_card('A');
+        natural.add_card('K');
+        assert!(natural.is_natural());
+        natural.add_card('T');
+        assert!(!natural.is_natural());
+    }
+
+    #[test]
+    fn hand_split() {
+        let mut hand = Hand::new(Vec::new());
+        hand.add_card('8');
+        hand.add_card('8');
+
+        assert!(hand.is_pair());
+        let second_hand = hand.split();
+        assert_eq!(hand.cards, vec!['8']);
+        assert_eq!(hand.total(), 8);
+        assert_eq!(second_hand.cards, vec!['8']);
+        assert_eq!(second_hand.total(), 8);
+        assert!(hand.split && second_hand.split);
+
+        hand.reset();
+        assert!(!hand.split);
+    }
 }
\ No newline at end of file
@@ -1,2 +1,21 @@
+pub mod action;
+pub mod betting;
+pub mod chart;
+pub mod composition;
+pub mod count;
+pub mod dealer;
+pub mod deviation;
+pub mod distraction;
+pub mod ev;
+pub mod explain;
 pub mod hand;
-pub mod shoe;
\ No newline at end of file
+pub mod profile;
+pub mod quiz;
+pub mod render;
+pub mod review;
+pub mod rules;
+pub mod shoe;
+pub mod solver;
+pub mod stats;
+pub mod strategy;
+pub mod wonging;
@@ -0,0 +1,319 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::game::chart::{self, Section, UPCARDS};
+use crate::game::quiz::QuizScore;
+use crate::game::render::CellScores;
+use crate::game::rules::Rules;
+
+pub const PROFILES_DIRECTORY : &str = "profiles";
+
+const SETTINGS_FILE : &str = "settings.txt";
+const CELLS_FILE    : &str = "cells.txt";
+const DRILLS_FILE   : &str = "drills.txt";
+
+const DAYS_PER_WEEK : u64 = 7;
+const WEAKEST_CELLS_SHOWN : usize = 5;
+
+/// 1 January 1970 was a Thursday, three days after the Monday that starts its week.
+const EPOCH_DAYS_AFTER_MONDAY : u64 = 3;
+
+/// Decisions made in one chart cell on one day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellRecord {
+    pub day     : u64,
+    pub section : Section,
+    pub row     : usize,
+    pub column  : usize,
+    pub score   : QuizScore,
+}
+
+impl CellRecord {
+    pub fn to_line(self) -> String {
+        return format!("{} {} {} {} {}", self.day, chart::row_label(self.section, self.row), UPCARDS[self.column], self.score.correct, self.score.asked);
+    }
+
+    pub fn from_line(line : &str) -> Option<CellRecord> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 5 {
+            return None;
+        }
+        let (section, row) = chart::label_cell(fields[1])?;
+        let upcard: char = fields[2].chars().next().filter(|upcard| UPCARDS.contains(upcard))?;
+        return Some(CellRecord {
+            day : fields[0].parse().ok()?,
+            section,
+            row,
+            column : chart::upcard_column(upcard),
+            score : QuizScore { correct : fields[3].parse().ok()?, asked : fields[4].parse().ok()? },
+        });
+    }
+}
+
+/// Answers given in one drill on one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrillRecord {
+    pub day   : u64,
+    pub drill : String,
+    pub score : QuizScore,
+}
+
+impl DrillRecord {
+    pub fn to_line(&self) -> String {
+        return format!("{} {} {} {}", self.day, self.drill, self.score.correct, self.score.asked);
+    }
+
+    pub fn from_line(line : &str) -> Option<DrillRecord> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 4 {
+            return None;
+        }
+        return Some(DrillRecord {
+            day : fields[0].parse().ok()?,
+            drill : fields[1].to_string(),
+            score : QuizScore { correct : fields[2].parse().ok()?, asked : fields[3].parse().ok()? },
+        });
+    }
+}
+
+/// A named player's preferences and progress, kept in a directory of its
+/// own under `profiles`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    name      : String,
+    directory : PathBuf,
+    pub rules    : Option<Rules>,
+    pub bankroll : Option<f64>,
+    pub lessons_passed : usize,
+    cells     : Vec<CellRecord>,
+    drills    : Vec<DrillRecord>,
+}
+
+impl Profile {
+    pub fn new(name : &str, directory : PathBuf) -> Profile {
+        Profile {
+            name : name.to_string(),
+            directory,
+            rules : None,
+            bankroll : None,
+            lessons_passed : 0,
+            cells : Vec::new(),
+            drills : Vec::new(),
+        }
+    }
+
+    /// Opens the profile called `name`, creating it if it doesn't exist yet.
+    /// Names are letters, digits, `-` and `_`.
+    pub fn open(name : &str) -> Result<Profile, String> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(format!("profile name '{}' should only have letters, digits, '-' and '_'", name));
+        }
+        let directory: PathBuf = PathBuf::from(PROFILES_DIRECTORY).join(name);
+        fs::create_dir_all(&directory).map_err(|error| format!("could not create profile '{}': {}", name, error))?;
+
+        let mut profile: Profile = Profile::new(name, directory);
+        let settings: String = fs::read_to_string(profile.directory.join(SETTINGS_FILE)).unwrap_or_default();
+        profile.parse_settings(&settings);
+        let cells: String = fs::read_to_string(profile.directory.join(CELLS_FILE)).unwrap_or_default();
+        profile.cells = cells.lines().filter_map(CellRecord::from_line).collect();
+        let drills: String = fs::read_to_string(profile.directory.join(DRILLS_FILE)).unwrap_or_default();
+        profile.drills = drills.lines().filter_map(DrillRecord::from_line).collect();
+        return Ok(profile);
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        fs::write(self.directory.join(SETTINGS_FILE), self.settings())?;
+        let cells: Vec<String> = self.cells.iter().map(|record| record.to_line() + "\n").collect();
+        fs::write(self.directory.join(CELLS_FILE), cells.concat())?;
+        let drills: Vec<String> = self.drills.iter().map(|record| record.to_line() + "\n").collect();
+        return fs::write(self.directory.join(DRILLS_FILE), drills.concat());
+    }
+
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+
+    /// Where the profile keeps a file of its own, such as its review schedule.
+    pub fn file(&self, file_name : &str) -> String {
+        return self.directory.join(file_name).to_string_lossy().into_owned();
+    }
+
+    fn settings(&self) -> String {
+        let mut settings: String = String::new();
+        if let Some(rules) = self.rules {
+            settings += &format!("rules {}\n", rules.code());
+        }
+        if let Some(bankroll) = self.bankroll {
+            settings += &format!("bankroll {}\n", bankroll);
+        }
+        if self.lessons_passed > 0 {
+            settings += &format!("lessons {}\n", self.lessons_passed);
+        }
+        return settings;
+    }
+
+    fn parse_settings(&mut self, text : &str) {
+        for line in text.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["rules", code]       => self.rules = Rules::from_code(code),
+                ["bankroll", bankroll] => self.bankroll = bankroll.parse().ok(),
+                ["lessons", passed]    => self.lessons_passed = passed.parse().unwrap_or(0),
+                _=> (),
+            }
+        }
+    }
+
+    pub fn record_decision(&mut self, day : u64, section : Section, row : usize, column : usize, correct : bool) {
+        let position: Option<usize> = self.cells.iter().position(|record| (record.day, record.section, record.row, record.column) == (day, section, row, column));
+        let index: usize = match position {
+            Some(index) => index,
+            None => {
+                self.cells.push(CellRecord { day, section, row, column, score : QuizScore::default() });
+                self.cells.len() - 1
+            },
+        };
+        self.cells[index].score.record(correct);
+    }
+
+    pub fn record_drill(&mut self, day : u64, drill : &str, score : QuizScore) {
+        let position: Option<usize> = self.drills.iter().position(|record| record.day == day && record.drill == drill);
+        let index: usize = match position {
+            Some(index) => index,
+            None => {
+                self.drills.push(DrillRecord { day, drill : drill.to_string(), score : QuizScore::default() });
+                self.drills.len() - 1
+            },
+        };
+        self.drills[index].score.asked += score.asked;
+        self.drills[index].score.correct += score.correct;
+    }
+
+    /// Accuracy in each chart cell over the whole history.
+    pub fn cell_scores(&self) -> CellScores {
+        let mut scores: CellScores = CellScores::new();
+        for record in self.cells.iter() {
+            let score: &mut QuizScore = scores.entry((record.section, record.row, record.column)).or_default();
+            score.asked += record.score.asked;
+            score.correct += record.score.correct;
+        }
+        return scores;
+    }
+
+    /// Strategy decisions and each drill's answers totalled by the week
+    /// they were made in, keyed by the day the week starts.
+    pub fn weekly_scores(&self) -> BTreeMap<u64, BTreeMap<String, QuizScore>> {
+        let mut weeks: BTreeMap<u64, BTreeMap<String, QuizScore>> = BTreeMap::new();
+        let records = self.cells.iter().map(|record| (record.day, "strategy", record.score))
+                                       .chain(self.drills.iter().map(|record| (record.day, record.drill.as_str(), record.score)));
+        for (day, name, record_score) in records {
+            let score: &mut QuizScore = weeks.entry(week_start(day)).or_default().entry(name.to_string()).or_default();
+            score.asked += record_score.asked;
+            score.correct += record_score.correct;
+        }
+        return weeks;
+    }
+
+    pub fn display(&self) {
+        println!("Profile {}", self.name);
+        if let Some(rules) = self.rules {
+            println!("Rules: {}", rules.code());
+        }
+        if let Some(bankroll) = self.bankroll {
+            println!("Bankroll: {} units", bankroll);
+        }
+        for (week, scores) in self.weekly_scores() {
+            let scores: Vec<String> = scores.iter().map(|(name, score)| format!("{} {}/{} ({:.0}%)", name, score.correct, score.asked, score.accuracy() * 100.0)).collect();
+            println!("Week of {}: {}", date(week), scores.join(", "));
+        }
+
+        let mut cells: Vec<((Section, usize, usize), QuizScore)> = self.cell_scores().into_iter().filter(|(_cell, score)| score.correct < score.asked).collect();
+        cells.sort_by(|a, b| a.1.accuracy().total_cmp(&b.1.accuracy()).then(b.1.asked.cmp(&a.1.asked)));
+        let cells: Vec<String> = cells.iter().take(WEAKEST_CELLS_SHOWN)
+                                      .map(|((section, row, column), score)| format!("{} vs {} {}/{}", chart::row_label(*section, *row), UPCARDS[*column], score.correct, score.asked))
+                                      .collect();
+        if !cells.is_empty() {
+            println!("Weakest cells: {}", cells.join(", "));
+        }
+    }
+}
+
+/// The Monday on or before `day`.
+pub fn week_start(day : u64) -> u64 {
+    return day - (day + EPOCH_DAYS_AFTER_MONDAY) % DAYS_PER_WEEK;
+}
+
+/// `day` days after 1 January 1970 as a `YYYY-MM-DD` date.
+pub fn date(day : u64) -> String {
+    // Civil from days, counting in 400 year eras that start on 1 March.
+    let days: i64 = day as i64 + 719468;
+    let era: i64 = days.div_euclid(146097);
+    let day_of_era: i64 = days - era * 146097;
+    let year_of_era: i64 = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year: i64 = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march: i64 = (5 * day_of_year + 2) / 153;
+    let day_of_month: i64 = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month: i64 = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 };
+    let year: i64 = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    return format!("{:04}-{:02}-{:02}", year, month, day_of_month);
+}
+
+
+#[cfg(test)]
+mod profile_tests {
+    use super::*;
+
+    #[test]
+    fn dates_and_weeks() {
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(date(11016), "2000-02-29");
+        assert_eq!(date(20745), "2026-10-19");
+        assert_eq!(week_start(20745), 20745);
+        assert_eq!(week_start(20751), 20745);
+        assert_eq!(week_start(20752), 20752);
+    }
+
+    #[test]
+    fn records_round_trip_through_lines() {
+        let record = CellRecord { day : 20745, section : Section::Soft, row : 5, column : 7, score : QuizScore { asked : 3, correct : 2 } };
+        assert_eq!(record.to_line(), "20745 A7 9 2 3");
+        assert_eq!(CellRecord::from_line(&record.to_line()), Some(record));
+
+        let record = DrillRecord { day : 20745, drill : "flash".to_string(), score : QuizScore { asked : 20, correct : 17 } };
+        assert_eq!(DrillRecord::from_line(&record.to_line()), Some(record));
+        assert!(DrillRecord::from_line("20745 flash 17").is_none());
+    }
+
+    #[test]
+    fn progress_totals_by_cell_and_week() {
+        let mut profile = Profile::new("test", PathBuf::new());
+        profile.record_decision(20745, Section::Hard, 11, 8, false);
+        profile.record_decision(20745, Section::Hard, 11, 8, true);
+        profile.record_decision(20753, Section::Hard, 11, 8, true);
+        profile.record_drill(20746, "flash", QuizScore { asked : 10, correct : 7 });
+        profile.record_drill(20746, "flash", QuizScore { asked : 10, correct : 9 });
+
+        assert_eq!(profile.cells.len(), 2);
+        assert_eq!(profile.cell_scores()[&(Section::Hard, 11, 8)], QuizScore { asked : 3, correct : 2 });
+
+        let weeks = profile.weekly_scores();
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[&20745]["strategy"], QuizScore { asked : 2, correct : 1 });
+        assert_eq!(weeks[&20745]["flash"], QuizScore { asked : 20, correct : 16 });
+        assert_eq!(weeks[&20752]["strategy"], QuizScore { asked : 1, correct : 1 });
+    }
+
+    #[test]
+    fn settings_round_trip() {
+        let mut profile = Profile::new("test", PathBuf::new());
+        profile.rules = Some(Rules { dealer_hits_soft_17 : true, double_after_split : true, late_surrender : false });
+        profile.bankroll = Some(250.5);
+        profile.lessons_passed = 3;
+
+        let mut loaded = Profile::new("test", PathBuf::new());
+        loaded.parse_settings(&profile.settings());
+        assert_eq!(loaded, profile);
+    }
+}
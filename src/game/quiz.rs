@@ -0,0 +1,147 @@
+use rand::{thread_rng, Rng};
+
+/// Moments in the game at which the count can be quizzed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuizPoint {
+    EndOfRound,
+    BeforeBet,
+    Shuffle,
+}
+
+/// Answers given to one kind of question.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuizScore {
+    pub asked   : u32,
+    pub correct : u32,
+}
+
+impl QuizScore {
+    pub fn record(&mut self, correct : bool) {
+        self.asked += 1;
+        if correct {
+            self.correct += 1;
+        }
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        if self.asked == 0 {
+            return 0.0;
+        }
+        return self.correct as f64 / self.asked as f64;
+    }
+}
+
+/// Asks for the count at random points during play and keeps score for the session.
+#[derive(Debug, Clone)]
+pub struct CountQuiz {
+    frequency     : f64,
+    running_count : QuizScore,
+    true_count    : QuizScore,
+    aces          : QuizScore,
+}
+
+impl CountQuiz {
+    /// `frequency` is the chance of a question at the end of a round or
+    /// before a bet. The last count of a shoe is always asked for.
+    pub fn new(frequency : f64) -> CountQuiz {
+        CountQuiz {
+            frequency,
+            running_count : QuizScore::default(),
+            true_count : QuizScore::default(),
+            aces : QuizScore::default(),
+        }
+    }
+
+    pub fn should_ask(&self, point : QuizPoint) -> bool {
+        match point {
+            QuizPoint::Shuffle => return true,
+            QuizPoint::EndOfRound | QuizPoint::BeforeBet => return thread_rng().gen_bool(self.frequency),
+        }
+    }
+
+    pub fn grade_running_count(&mut self, answer : f64, running_count : f64) -> bool {
+        let correct: bool = running_count_correct(answer, running_count);
+        self.running_count.record(correct);
+        return correct;
+    }
+
+    pub fn grade_true_count(&mut self, answer : f64, true_count : f64) -> bool {
+        let correct: bool = true_count_correct(answer, true_count);
+        self.true_count.record(correct);
+        return correct;
+    }
+
+    /// The ace side count has to be exact.
+    pub fn grade_aces(&mut self, answer : f64, aces_remaining : u16) -> bool {
+        let correct: bool = answer == aces_remaining as f64;
+        self.aces.record(correct);
+        return correct;
+    }
+
+    pub fn running_count_score(&self) -> QuizScore {
+        return self.running_count;
+    }
+
+    pub fn true_count_score(&self) -> QuizScore {
+        return self.true_count;
+    }
+
+    pub fn aces_score(&self) -> QuizScore {
+        return self.aces;
+    }
+
+    pub fn display(&self) {
+        for (name, score) in [("Running count", self.running_count), ("True count", self.true_count), ("Aces remaining", self.aces)] {
+            if score.asked > 0 {
+                println!("{}: {}/{} correct ({:.0}%)", name, score.correct, score.asked, score.accuracy() * 100.0);
+            }
+        }
+    }
+}
+
+/// The running count has to be exact.
+pub fn running_count_correct(answer : f64, running_count : f64) -> bool {
+    return (answer - running_count).abs() < 1e-9;
+}
+
+/// The true count only has to be within one of the exact value, so both
+/// rounding and flooring the division are accepted.
+pub fn true_count_correct(answer : f64, true_count : f64) -> bool {
+    return (answer - true_count).abs() < 1.0;
+}
+
+
+#[cfg(test)]
+mod quiz_tests {
+    use super::*;
+
+    #[test]
+    fn quiz_grading() {
+        assert!(running_count_correct(4.0, 4.0));
+        assert!(!running_count_correct(3.0, 4.0));
+        assert!(running_count_correct(-1.5, -1.5));
+
+        assert!(true_count_correct(2.0, 2.6));
+        assert!(true_count_correct(3.0, 2.6));
+        assert!(!true_count_correct(4.0, 2.6));
+        assert!(!true_count_correct(-2.0, -0.9));
+    }
+
+    #[test]
+    fn quiz_keeps_score() {
+        let mut quiz = CountQuiz::new(0.0);
+        assert!(!quiz.should_ask(QuizPoint::EndOfRound));
+        assert!(quiz.should_ask(QuizPoint::Shuffle));
+
+        quiz.grade_running_count(5.0, 5.0);
+        quiz.grade_running_count(4.0, 5.0);
+        quiz.grade_true_count(2.0, 2.5);
+        assert_eq!(quiz.running_count_score(), QuizScore { asked : 2, correct : 1 });
+        assert_eq!(quiz.true_count_score(), QuizScore { asked : 1, correct : 1 });
+        assert_eq!(quiz.running_count_score().accuracy(), 0.5);
+
+        assert!(quiz.grade_aces(7.0, 7));
+        assert!(!quiz.grade_aces(6.0, 7));
+        assert_eq!(quiz.aces_score(), QuizScore { asked : 2, correct : 1 });
+    }
+}
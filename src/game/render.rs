@@ -0,0 +1,397 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::game::chart::{Chart, ChartAction, Section, UPCARDS, section_name, row_label};
+use crate::game::quiz::QuizScore;
+
+const RESET : &str = "\x1b[0m";
+
+/// Number of times each chart cell, keyed by section, row and upcard column,
+/// was played wrongly.
+pub type CellCounts = HashMap<(Section, usize, usize), u32>;
+
+/// Right and wrong plays in each chart cell, keyed like `CellCounts`.
+pub type CellScores = BTreeMap<(Section, usize, usize), QuizScore>;
+
+/// Heatmap cells are green from this accuracy and red below the lower one.
+const GOOD_ACCURACY : f64 = 0.85;
+const POOR_ACCURACY : f64 = 0.6;
+
+const SVG_LABEL_WIDTH : usize = 48;
+const SVG_CELL_WIDTH  : usize = 36;
+const SVG_ROW_HEIGHT  : usize = 22;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartFormat {
+    Terminal,
+    Text,
+    Markdown,
+    Html,
+    Svg,
+}
+
+impl ChartFormat {
+    pub fn from_name(name : &str) -> Option<ChartFormat> {
+        match name {
+            "terminal"        => Some(ChartFormat::Terminal),
+            "text"            => Some(ChartFormat::Text),
+            "md" | "markdown" => Some(ChartFormat::Markdown),
+            "html"            => Some(ChartFormat::Html),
+            "svg"             => Some(ChartFormat::Svg),
+            _=> None,
+        }
+    }
+}
+
+/// Renders `chart` with player hands as rows and dealer upcards as columns.
+/// Cells with an entry in `mistakes` are highlighted.
+pub fn render(chart : &Chart, format : ChartFormat, mistakes : Option<&CellCounts>) -> String {
+    let no_mistakes: CellCounts = CellCounts::new();
+    let mistakes: &CellCounts = mistakes.unwrap_or(&no_mistakes);
+
+    if format == ChartFormat::Svg {
+        let sections: Vec<SvgSection> = svg_sections(chart, |section, row, column, action| {
+            let missed: Option<&u32> = mistakes.get(&(section, row, column));
+            let title: String = format!("{} vs {}: {}{}", row_label(section, row), UPCARDS[column], action.code(),
+                                        missed.map_or(String::new(), |count| format!(", missed {}", count)));
+            return SvgCell { text : action.code().to_string(), fill : action_fill(action).to_string(), bold : missed.is_some(), title };
+        });
+        return svg(&sections, most_missed(mistakes, format).trim_end());
+    }
+
+    let mut output: String = String::new();
+    if format == ChartFormat::Html {
+        output += HTML_HEADER;
+    }
+
+    for section in [Section::Hard, Section::Soft, Section::Pairs] {
+        output += &header_row(section, format);
+        for (row, actions) in chart.rows(section).iter().enumerate() {
+            let cells: Vec<String> = actions.iter()
+                                            .enumerate()
+                                            .map(|(column, action)| render_cell(*action, mistakes.contains_key(&(section, row, column)), format))
+                                            .collect();
+            output += &body_row(&row_label(section, row), &cells, format);
+        }
+        output += match format {
+            ChartFormat::Html => "</table>\n",
+            _=> "\n",
+        };
+    }
+
+    output += &most_missed(mistakes, format);
+    if format == ChartFormat::Html {
+        output += "</body>\n</html>\n";
+    }
+    return output;
+}
+
+const HTML_HEADER : &str = "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Basic Strategy</title>
+<style>
+table { border-collapse: collapse; margin-bottom: 1em; font-family: monospace; }
+th, td { border: 1px solid #444; padding: 2px 6px; text-align: center; }
+.H { background: #f28b82; } .S { background: #fdd663; } .D, .Ds { background: #81c995; }
+.P { background: #8ab4f8; } .Rh, .Rs, .Rp { background: #e8eaed; }
+.missed { outline: 3px solid #000; font-weight: bold; }
+</style>
+</head>
+<body>
+";
+
+fn header_row(section : Section, format : ChartFormat) -> String {
+    let upcards: Vec<String> = UPCARDS.iter().map(|upcard| upcard.to_string()).collect();
+    match format {
+        ChartFormat::Terminal | ChartFormat::Text => {
+            let mut row: String = format!("{:<5}", section_name(section));
+            for upcard in upcards {
+                row += &format!(" {:^3}", upcard);
+            }
+            return row + "\n";
+        },
+        ChartFormat::Markdown => {
+            return format!("| {} | {} |\n|---|{}\n", section_name(section), upcards.join(" | "), "---|".repeat(UPCARDS.len()));
+        },
+        ChartFormat::Html | ChartFormat::Svg => {
+            return format!("<table>\n<tr><th>{}</th><th>{}</th></tr>\n", section_name(section), upcards.join("</th><th>"));
+        },
+    }
+}
+
+fn body_row(label : &str, cells : &[String], format : ChartFormat) -> String {
+    match format {
+        ChartFormat::Terminal | ChartFormat::Text => return format!("{:<5} {}\n", label, cells.join(" ")),
+        ChartFormat::Markdown => return format!("| {} | {} |\n", label, cells.join(" | ")),
+        ChartFormat::Html | ChartFormat::Svg => return format!("<tr><th>{}</th>{}</tr>\n", label, cells.join("")),
+    }
+}
+
+fn render_cell(action : ChartAction, missed : bool, format : ChartFormat) -> String {
+    let code: &str = action.code();
+    match format {
+        ChartFormat::Terminal => {
+            let highlight: &str = if missed { "\x1b[1;4m" } else { "" };
+            return format!("{}{}{:^3}{}", action_colour(action), highlight, code, RESET);
+        },
+        ChartFormat::Text => {
+            return format!("{:<3}", if missed { format!("{}*", code) } else { code.to_string() });
+        },
+        ChartFormat::Markdown => {
+            return if missed { format!("**{}**", code) } else { code.to_string() };
+        },
+        ChartFormat::Html | ChartFormat::Svg => {
+            return format!("<td class=\"{}{}\">{}</td>", code, if missed { " missed" } else { "" }, code);
+        },
+    }
+}
+
+/// Black text on a background colour for each kind of play.
+fn action_colour(action : ChartAction) -> &'static str {
+    match action {
+        ChartAction::Hit => "\x1b[30;41m",
+        ChartAction::Stand => "\x1b[30;43m",
+        ChartAction::DoubleOrHit | ChartAction::DoubleOrStand => "\x1b[30;42m",
+        ChartAction::Split => "\x1b[30;44m",
+        ChartAction::SurrenderOrHit | ChartAction::SurrenderOrStand | ChartAction::SurrenderOrSplit => "\x1b[30;47m",
+    }
+}
+
+/// Renders the chart grid with each cell showing how often it was played
+/// correctly according to `scores`, coloured from red to green. Cells
+/// without any plays are left blank.
+pub fn render_heatmap(chart : &Chart, scores : &CellScores, format : ChartFormat) -> String {
+    if format == ChartFormat::Svg {
+        let sections: Vec<SvgSection> = svg_sections(chart, |section, row, column, action| {
+            let score: Option<&QuizScore> = scores.get(&(section, row, column)).filter(|score| score.asked > 0);
+            let title: String = format!("{} vs {}: {}, {}", row_label(section, row), UPCARDS[column], action.code(),
+                                        score.map_or("not played".to_string(), |score| format!("{}/{} correct", score.correct, score.asked)));
+            return SvgCell {
+                text : score.map_or(String::new(), |score| format!("{:.0}", score.accuracy() * 100.0)),
+                fill : score.map_or("#ffffff".to_string(), |score| accuracy_fill(score.accuracy())),
+                bold : false,
+                title,
+            };
+        });
+        return svg(&sections, "Accuracy by cell, % correct");
+    }
+
+    let mut output: String = String::new();
+    if format == ChartFormat::Html {
+        output += &HTML_HEADER.replace("Basic Strategy", "Accuracy by cell");
+    }
+
+    for section in [Section::Hard, Section::Soft, Section::Pairs] {
+        output += &header_row(section, format);
+        for (row, actions) in chart.rows(section).iter().enumerate() {
+            let cells: Vec<String> = actions.iter()
+                                            .enumerate()
+                                            .map(|(column, action)| heatmap_cell(*action, scores.get(&(section, row, column)).filter(|score| score.asked > 0), format))
+                                            .collect();
+            output += &body_row(&row_label(section, row), &cells, format);
+        }
+        output += match format {
+            ChartFormat::Html => "</table>\n",
+            _=> "\n",
+        };
+    }
+
+    let legend: String = format!("Accuracy by cell, % correct: green from {:.0}%, red below {:.0}%, blank if not played", GOOD_ACCURACY * 100.0, POOR_ACCURACY * 100.0);
+    match format {
+        ChartFormat::Html => output += &format!("<p>{}</p>\n</body>\n</html>\n", legend),
+        _=> output += &(legend + "\n"),
+    }
+    return output;
+}
+
+fn heatmap_cell(action : ChartAction, score : Option<&QuizScore>, format : ChartFormat) -> String {
+    let percentage: String = score.map_or(String::new(), |score| format!("{:.0}", score.accuracy() * 100.0));
+    match format {
+        ChartFormat::Terminal => {
+            return match score {
+                Some(score) => format!("{}{:^3}{}", accuracy_colour(score.accuracy()), percentage, RESET),
+                None => format!("{:^3}", "."),
+            };
+        },
+        ChartFormat::Text => return format!("{:^3}", if score.is_some() { percentage.as_str() } else { "." }),
+        ChartFormat::Markdown => return percentage,
+        ChartFormat::Html | ChartFormat::Svg => {
+            let title: String = format!("{}, {}", action.code(), score.map_or("not played".to_string(), |score| format!("{}/{} correct", score.correct, score.asked)));
+            let fill: String = score.map_or("#ffffff".to_string(), |score| accuracy_fill(score.accuracy()));
+            return format!("<td style=\"background: {}\" title=\"{}\">{}</td>", fill, title, percentage);
+        },
+    }
+}
+
+/// Black text on red, yellow or green by accuracy band.
+fn accuracy_colour(accuracy : f64) -> &'static str {
+    if accuracy >= GOOD_ACCURACY {
+        return "\x1b[30;42m";
+    }
+    if accuracy >= POOR_ACCURACY {
+        return "\x1b[30;43m";
+    }
+    return "\x1b[30;41m";
+}
+
+/// A colour running from red at no correct plays to green at all correct.
+fn accuracy_fill(accuracy : f64) -> String {
+    return format!("hsl({:.0}, 70%, 65%)", accuracy * 120.0);
+}
+
+/// The same colours the HTML chart uses for each kind of play.
+fn action_fill(action : ChartAction) -> &'static str {
+    match action {
+        ChartAction::Hit => "#f28b82",
+        ChartAction::Stand => "#fdd663",
+        ChartAction::DoubleOrHit | ChartAction::DoubleOrStand => "#81c995",
+        ChartAction::Split => "#8ab4f8",
+        ChartAction::SurrenderOrHit | ChartAction::SurrenderOrStand | ChartAction::SurrenderOrSplit => "#e8eaed",
+    }
+}
+
+struct SvgCell {
+    text  : String,
+    fill  : String,
+    bold  : bool,
+    title : String,
+}
+
+/// A section name and its rows, each a label and a cell per upcard.
+type SvgSection = (Section, Vec<(String, Vec<SvgCell>)>);
+
+fn svg_sections(chart : &Chart, cell : impl Fn(Section, usize, usize, ChartAction) -> SvgCell) -> Vec<SvgSection> {
+    let mut sections: Vec<SvgSection> = Vec::new();
+    for section in [Section::Hard, Section::Soft, Section::Pairs] {
+        let rows: Vec<(String, Vec<SvgCell>)> = chart.rows(section).iter()
+                                                     .enumerate()
+                                                     .map(|(row, actions)| (row_label(section, row),
+                                                                            actions.iter().enumerate().map(|(column, action)| cell(section, row, column, *action)).collect()))
+                                                     .collect();
+        sections.push((section, rows));
+    }
+    return sections;
+}
+
+/// Draws the sections one under the other with a caption underneath.
+fn svg(sections : &[SvgSection], caption : &str) -> String {
+    let width: usize = SVG_LABEL_WIDTH + SVG_CELL_WIDTH * UPCARDS.len();
+    let num_of_rows: usize = sections.iter().map(|(_section, rows)| rows.len() + 2).sum::<usize>() + 1;
+    let mut output: String = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"monospace\" font-size=\"12\">\n",
+                                     width,
+                                     num_of_rows * SVG_ROW_HEIGHT);
+
+    let mut y: usize = 0;
+    for (section, rows) in sections {
+        output += &svg_text(0, y, SVG_LABEL_WIDTH, section_name(*section), true);
+        for (column, upcard) in UPCARDS.iter().enumerate() {
+            output += &svg_text(SVG_LABEL_WIDTH + column * SVG_CELL_WIDTH, y, SVG_CELL_WIDTH, &upcard.to_string(), true);
+        }
+        y += SVG_ROW_HEIGHT;
+
+        for (label, cells) in rows {
+            output += &svg_text(0, y, SVG_LABEL_WIDTH, label, true);
+            for (column, cell) in cells.iter().enumerate() {
+                let x: usize = SVG_LABEL_WIDTH + column * SVG_CELL_WIDTH;
+                output += &format!("<g><title>{}</title><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"#444\"/>{}</g>\n",
+                                   cell.title, x, y, SVG_CELL_WIDTH, SVG_ROW_HEIGHT, cell.fill, svg_text(x, y, SVG_CELL_WIDTH, &cell.text, cell.bold).trim_end());
+            }
+            y += SVG_ROW_HEIGHT;
+        }
+        y += SVG_ROW_HEIGHT;
+    }
+
+    output += &format!("<text x=\"0\" y=\"{}\">{}</text>\n</svg>\n", y + SVG_ROW_HEIGHT * 2 / 3, caption);
+    return output;
+}
+
+/// Text centred in the cell `width` wide whose top left corner is at `x`, `y`.
+fn svg_text(x : usize, y : usize, width : usize, text : &str, bold : bool) -> String {
+    return format!("<text x=\"{}\" y=\"{}\" text-anchor=\"middle\"{}>{}</text>\n",
+                   x + width / 2,
+                   y + SVG_ROW_HEIGHT * 2 / 3,
+                   if bold { " font-weight=\"bold\"" } else { "" },
+                   text);
+}
+
+/// Lists the most often missed cells, worst first.
+fn most_missed(mistakes : &CellCounts, format : ChartFormat) -> String {
+    if mistakes.is_empty() {
+        return String::new();
+    }
+
+    let mut cells: Vec<(&(Section, usize, usize), &u32)> = mistakes.iter().collect();
+    cells.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+    let descriptions: Vec<String> = cells.iter()
+                                         .take(5)
+                                         .map(|((section, row, column), count)| format!("{} vs {} ({})", row_label(*section, *row), UPCARDS[*column], count))
+                                         .collect();
+    let line: String = format!("Most missed: {}", descriptions.join(", "));
+    match format {
+        ChartFormat::Html => return format!("<p>{}</p>\n", line),
+        _=> return line + "\n",
+    }
+}
+
+
+#[cfg(test)]
+mod render_tests {
+    use super::*;
+
+    #[test]
+    fn render_text_matches_chart_layout() {
+        let chart = Chart::basic_strategy();
+        let text = render(&chart, ChartFormat::Text, None);
+        assert!(text.starts_with("Hard   2   3   4   5   6   7   8   9   T   A \n"));
+        assert!(text.contains("16    S   S   S   S   S   H   H   Rh  Rh  Rh \n"));
+        assert!(!text.contains("Most missed"));
+    }
+
+    #[test]
+    fn render_highlights_mistakes() {
+        let chart = Chart::basic_strategy();
+        let mut mistakes = CellCounts::new();
+        mistakes.insert((Section::Hard, 11, 8), 3);
+        mistakes.insert((Section::Soft, 5, 1), 1);
+
+        let markdown = render(&chart, ChartFormat::Markdown, Some(&mistakes));
+        assert!(markdown.contains("| 16 | S | S | S | S | S | H | H | Rh | **Rh** | Rh |"));
+        assert!(markdown.contains("Most missed: 16 vs T (3), A7 vs 3 (1)"));
+
+        let html = render(&chart, ChartFormat::Html, Some(&mistakes));
+        assert!(html.contains("<td class=\"Rh missed\">Rh</td>"));
+        assert!(html.ends_with("</html>\n"));
+    }
+
+    #[test]
+    fn heatmap_shows_accuracy_by_cell() {
+        let chart = Chart::basic_strategy();
+        let mut scores = CellScores::new();
+        scores.insert((Section::Hard, 11, 8), QuizScore { asked : 4, correct : 1 });
+        scores.insert((Section::Soft, 5, 1), QuizScore { asked : 2, correct : 2 });
+
+        let text = render_heatmap(&chart, &scores, ChartFormat::Text);
+        assert!(text.contains("16     .   .   .   .   .   .   .   .  25   . \n"));
+        assert!(text.contains("A7     .  100  .   .   .   .   .   .   .   . \n"));
+
+        let html = render_heatmap(&chart, &scores, ChartFormat::Html);
+        assert!(html.contains("<td style=\"background: hsl(30, 70%, 65%)\" title=\"Rh, 1/4 correct\">25</td>"));
+        assert!(html.ends_with("</html>\n"));
+    }
+
+    #[test]
+    fn svg_draws_every_cell() {
+        let chart = Chart::basic_strategy();
+        let svg = render(&chart, ChartFormat::Svg, None);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<rect ").count(), (16 + 8 + 10) * UPCARDS.len());
+        assert!(svg.contains("<title>16 vs T: Rh</title>"));
+
+        let mut scores = CellScores::new();
+        scores.insert((Section::Pairs, 9, 0), QuizScore { asked : 1, correct : 0 });
+        assert!(render_heatmap(&chart, &scores, ChartFormat::Svg).contains("<title>AA vs 2: P, 0/1 correct</title>"));
+    }
+}
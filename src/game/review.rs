@@ -0,0 +1,211 @@
+use std::fs;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::game::chart::{self, Section, UPCARDS};
+
+pub const REVIEW_FILE : &str = "review_schedule.txt";
+
+const SECONDS_PER_DAY : u64 = 24 * 60 * 60;
+
+/// SM-2 starts every item at this ease and never lets it drop below the minimum.
+const INITIAL_EASE : f64 = 2.5;
+const MINIMUM_EASE : f64 = 1.3;
+
+/// Answer qualities on the SM-2 scale of 0 to 5. Anything below three is a lapse.
+const QUALITY_CORRECT : u8 = 4;
+const QUALITY_WRONG   : u8 = 1;
+const QUALITY_PASS    : u8 = 3;
+
+/// A strategy situation that has been missed: a chart cell under some
+/// rules, with when it is next due for review.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewItem {
+    pub section     : Section,
+    pub row         : usize,
+    pub column      : usize,
+    pub rules       : String,
+    pub ease        : f64,
+    pub interval    : u32,
+    pub repetitions : u32,
+    pub due         : u64,
+}
+
+impl ReviewItem {
+    fn new(section : Section, row : usize, column : usize, rules : &str, today : u64) -> ReviewItem {
+        ReviewItem {
+            section,
+            row,
+            column,
+            rules : rules.to_string(),
+            ease : INITIAL_EASE,
+            interval : 0,
+            repetitions : 0,
+            due : today,
+        }
+    }
+
+    /// Reschedules the item after an answer of `quality` on day `today`.
+    pub fn review(&mut self, quality : u8, today : u64) {
+        if quality < QUALITY_PASS {
+            self.repetitions = 0;
+            self.interval = 1;
+        }
+        else {
+            self.interval = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _=> (self.interval as f64 * self.ease).round() as u32,
+            };
+            self.repetitions += 1;
+        }
+
+        let misses: f64 = (5 - quality.min(5)) as f64;
+        self.ease = (self.ease + 0.1 - misses * (0.08 + misses * 0.02)).max(MINIMUM_EASE);
+        self.due = today + self.interval as u64;
+    }
+
+    pub fn describe(&self) -> String {
+        return format!("{} vs {}", chart::row_label(self.section, self.row), UPCARDS[self.column]);
+    }
+
+    pub fn to_line(&self) -> String {
+        return format!("{} {} {} {:.2} {} {} {}",
+                       chart::row_label(self.section, self.row),
+                       UPCARDS[self.column],
+                       self.rules,
+                       self.ease,
+                       self.interval,
+                       self.repetitions,
+                       self.due);
+    }
+
+    pub fn from_line(line : &str) -> Option<ReviewItem> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 7 {
+            return None;
+        }
+        let (section, row) = chart::label_cell(fields[0])?;
+        let upcard: char = fields[1].chars().next().filter(|upcard| UPCARDS.contains(upcard))?;
+        return Some(ReviewItem {
+            section,
+            row,
+            column : chart::upcard_column(upcard),
+            rules : fields[2].to_string(),
+            ease : fields[3].parse().ok()?,
+            interval : fields[4].parse().ok()?,
+            repetitions : fields[5].parse().ok()?,
+            due : fields[6].parse().ok()?,
+        });
+    }
+}
+
+/// Missed situations scheduled for review with SM-2.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReviewSchedule {
+    items : Vec<ReviewItem>,
+}
+
+impl ReviewSchedule {
+    pub fn new() -> ReviewSchedule {
+        ReviewSchedule {
+            items : Vec::new(),
+        }
+    }
+
+    /// A missing file is an empty schedule.
+    pub fn load(path : &str) -> ReviewSchedule {
+        let text: String = fs::read_to_string(path).unwrap_or_default();
+        return ReviewSchedule {
+            items : text.lines().filter_map(ReviewItem::from_line).collect(),
+        };
+    }
+
+    pub fn save(&self, path : &str) -> io::Result<()> {
+        let lines: Vec<String> = self.items.iter().map(|item| item.to_line() + "\n").collect();
+        return fs::write(path, lines.concat());
+    }
+
+    /// Records an answer in a chart cell. A miss schedules the cell if it
+    /// isn't already; answers in cells that were never missed are ignored.
+    pub fn record(&mut self, section : Section, row : usize, column : usize, rules : &str, correct : bool, today : u64) {
+        let position: Option<usize> = self.items.iter().position(|item| (item.section, item.row, item.column) == (section, row, column) && item.rules == rules);
+        let index: usize = match position {
+            Some(index) => index,
+            None if correct => return,
+            None => {
+                self.items.push(ReviewItem::new(section, row, column, rules, today));
+                self.items.len() - 1
+            },
+        };
+        self.items[index].review(if correct { QUALITY_CORRECT } else { QUALITY_WRONG }, today);
+    }
+
+    /// Items under `rules` due on or before `today`, the most overdue and
+    /// then the hardest first.
+    pub fn due(&self, rules : &str, today : u64) -> Vec<&ReviewItem> {
+        let mut due: Vec<&ReviewItem> = self.items.iter().filter(|item| item.rules == rules && item.due <= today).collect();
+        due.sort_by(|a, b| a.due.cmp(&b.due).then(a.ease.total_cmp(&b.ease)));
+        return due;
+    }
+}
+
+/// Days since the Unix epoch.
+pub fn today() -> u64 {
+    let seconds: u64 = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    return seconds / SECONDS_PER_DAY;
+}
+
+
+#[cfg(test)]
+mod review_tests {
+    use super::*;
+
+    #[test]
+    fn sm2_intervals() {
+        let mut item = ReviewItem::new(Section::Hard, 11, 8, "S17-DAS-LS", 100);
+        item.review(QUALITY_WRONG, 100);
+        assert_eq!((item.interval, item.due), (1, 101));
+        assert!(item.ease < INITIAL_EASE);
+
+        item.review(QUALITY_CORRECT, 101);
+        assert_eq!(item.interval, 1);
+        item.review(QUALITY_CORRECT, 102);
+        assert_eq!(item.interval, 6);
+        item.review(QUALITY_CORRECT, 108);
+        assert!(item.interval > 6);
+        assert_eq!(item.due, 108 + item.interval as u64);
+
+        for _lapse in 0..20 {
+            item.review(QUALITY_WRONG, 200);
+        }
+        assert_eq!(item.ease, MINIMUM_EASE);
+        assert_eq!(item.repetitions, 0);
+    }
+
+    #[test]
+    fn schedule_only_keeps_misses() {
+        let mut schedule = ReviewSchedule::new();
+        schedule.record(Section::Hard, 11, 8, "S17-DAS-LS", true, 100);
+        assert!(schedule.due("S17-DAS-LS", 100).is_empty());
+
+        schedule.record(Section::Hard, 11, 8, "S17-DAS-LS", false, 100);
+        schedule.record(Section::Soft, 5, 1, "S17-DAS-LS", false, 99);
+        assert_eq!(schedule.due("S17-DAS-LS", 100).len(), 1);
+        assert!(schedule.due("H17-DAS-LS", 101).is_empty());
+
+        let due = schedule.due("S17-DAS-LS", 101);
+        assert_eq!(due.len(), 2);
+        assert_eq!(due[0].describe(), "A7 vs 3");
+        assert_eq!(due[1].describe(), "16 vs T");
+    }
+
+    #[test]
+    fn review_item_round_trips_through_a_line() {
+        let mut item = ReviewItem::new(Section::Pairs, 9, 9, "H17-NDAS-NS", 20000);
+        item.review(QUALITY_WRONG, 20000);
+        assert_eq!(item.to_line(), "AA A H17-NDAS-NS 1.96 1 0 20001");
+        assert_eq!(ReviewItem::from_line(&item.to_line()).unwrap().to_line(), item.to_line());
+        assert!(ReviewItem::from_line("21 T S17-DAS-LS 2.5 1 0 1").is_none());
+    }
+}
@@ -0,0 +1,58 @@
+
+/// Table rules that change the correct play or the value of a hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rules {
+    pub dealer_hits_soft_17 : bool,
+    pub double_after_split  : bool,
+    pub late_surrender      : bool,
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            dealer_hits_soft_17 : false,
+            double_after_split  : true,
+            late_surrender      : true,
+        }
+    }
+}
+
+impl Rules {
+    /// A short name for the rules, e.g. `S17-DAS-LS`.
+    pub fn code(&self) -> String {
+        return format!("{}-{}-{}",
+                       if self.dealer_hits_soft_17 { "H17" } else { "S17" },
+                       if self.double_after_split { "DAS" } else { "NDAS" },
+                       if self.late_surrender { "LS" } else { "NS" });
+    }
+
+    pub fn from_code(code : &str) -> Option<Rules> {
+        let fields: Vec<&str> = code.split('-').collect();
+        if fields.len() != 3 {
+            return None;
+        }
+        let dealer_hits_soft_17: bool = match fields[0] { "H17" => true, "S17" => false, _=> return None };
+        let double_after_split: bool = match fields[1] { "DAS" => true, "NDAS" => false, _=> return None };
+        let late_surrender: bool = match fields[2] { "LS" => true, "NS" => false, _=> return None };
+        return Some(Rules {
+            dealer_hits_soft_17,
+            double_after_split,
+            late_surrender,
+        });
+    }
+}
+
+
+#[cfg(test)]
+mod rules_tests {
+    use super::*;
+
+    #[test]
+    fn rules_code() {
+        let rules = Rules { dealer_hits_soft_17 : true, double_after_split : false, late_surrender : true };
+        assert_eq!(rules.code(), "H17-NDAS-LS");
+        assert_eq!(Rules::default().code(), "S17-DAS-LS");
+        assert_eq!(Rules::from_code(&rules.code()), Some(rules));
+        assert_eq!(Rules::from_code("S17-DAS"), None);
+    }
+}
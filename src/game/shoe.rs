@@ -1,39 +1,54 @@
+use crate::game::composition::Composition;
+use crate::game::count::{Counter, CountingSystem};
 use crate::game::hand::Hand;
 
 use rand::thread_rng;
 use rand::seq::SliceRandom;
 
-const CARDS_PER_DECK : u8 = 52;
+pub const CARDS_PER_DECK : u8 = 52;
 const CARDS_PER_HAND : u8 = 2;
+pub const ACES_PER_DECK : u16 = 4;
+
+/// The thirteen cards of a suit, from two up to ace.
+pub const SUIT : [char; 13] = ['2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A'];
 
 #[derive(Debug)]
 pub struct Shoe {
     num_of_decks: u8,
-    num_of_cards: u8,
+    num_of_cards: u16,
     penetration_percentage: u8,
-    penetration_depth: u8,
+    penetration_depth: u16,
     cards: Vec<char>,
+    num_of_shuffles: u32,
+    hole_card: Option<char>,
+    // Log of the cards seen face up since the last shuffle.
+    seen_cards: Vec<char>,
+    counter: Counter,
 }
 
 impl Shoe {
     pub fn new(num_of_decks: u8, penetration_percentage: u8, cards : Vec<char>) -> Shoe {
-        let num_of_cards: u8 = num_of_decks * CARDS_PER_DECK;
+        let num_of_cards: u16 = num_of_decks as u16 * CARDS_PER_DECK as u16;
         let percentage: f32 = penetration_percentage as f32 / 100.0;
         let penetration_depth : f32 = num_of_cards as f32 * percentage;
 
         Shoe {
             num_of_decks,
-            num_of_cards : num_of_cards,
+            num_of_cards,
             penetration_percentage,
-            penetration_depth : penetration_depth as u8,
+            penetration_depth : penetration_depth as u16,
             cards,
+            num_of_shuffles : 0,
+            hole_card : None,
+            seen_cards : Vec::new(),
+            counter : Counter::new(CountingSystem::hi_lo(), num_of_decks),
         }
     }
 
     pub fn init(&mut self) {
         if self.num_of_decks == 0 ||
             self.penetration_percentage == 0 ||
-            self.cards.len() != 0 {
+            !self.cards.is_empty() {
             panic!();
         }
         
@@ -46,6 +61,9 @@ impl Shoe {
 
     pub fn reset(&mut self) {
         self.cards.clear();
+        self.hole_card = None;
+        self.seen_cards.clear();
+        self.counter.reset();
         self.init();
     }
 
@@ -57,27 +75,164 @@ impl Shoe {
     }
 
     pub fn add_suit(&mut self) {
-        self.cards.push('2');
-        self.cards.push('3');
-        self.cards.push('4');
-        self.cards.push('5');
-        self.cards.push('6');
-        self.cards.push('7');
-        self.cards.push('8');
-        self.cards.push('9');
-        self.cards.push('T');
-        self.cards.push('J');
-        self.cards.push('Q');
-        self.cards.push('K');
-        self.cards.push('A');
+        self.cards.extend(SUIT);
     }
 
     pub fn shuffle(&mut self) {
         self.cards.shuffle(&mut thread_rng());
+        self.num_of_shuffles += 1;
+    }
+
+    pub fn num_of_decks(&self) -> u8 {
+        return self.num_of_decks;
+    }
+
+    pub fn num_of_shuffles(&self) -> u32 {
+        return self.num_of_shuffles;
+    }
+
+    /// Composition of the cards that have not yet left the shoe.
+    pub fn composition(&self) -> Composition {
+        return Composition::from_cards(&self.cards);
+    }
+
+    /// Switches counting system, starting the count again from the current shoe.
+    pub fn set_counting_system(&mut self, system : CountingSystem) {
+        self.counter = Counter::new(system, self.num_of_decks);
+        self.hole_card = None;
+    }
+
+    pub fn counting_system(&self) -> &CountingSystem {
+        return self.counter.system();
+    }
+
+    pub fn running_count(&self) -> f64 {
+        return self.counter.running_count();
+    }
+
+    /// Decks not yet seen by the player, which includes a hidden hole card.
+    pub fn decks_remaining(&self) -> f64 {
+        let unseen_cards: usize = self.cards.len() + self.hole_card.map_or(0, |_card| 1);
+        return unseen_cards as f64 / CARDS_PER_DECK as f64;
+    }
+
+    pub fn true_count(&self) -> f64 {
+        return self.counter.true_count(self.decks_remaining());
+    }
+
+    pub fn aces_remaining(&self) -> u16 {
+        let aces_seen: usize = self.seen_cards.iter().filter(|card| **card == 'A').count();
+        return self.num_of_decks as u16 * ACES_PER_DECK - aces_seen as u16;
+    }
+
+    /// Aces left beyond the shoe's fair share of them, negative when the
+    /// shoe is ace poor.
+    pub fn ace_surplus(&self) -> f64 {
+        return self.aces_remaining() as f64 - self.decks_remaining() * ACES_PER_DECK as f64;
+    }
+
+    /// True count for sizing bets, with the ace surplus folded back in for
+    /// systems that side count aces.
+    pub fn betting_true_count(&self) -> f64 {
+        let system: &CountingSystem = self.counter.system();
+        if !system.has_ace_side_count() {
+            return self.true_count();
+        }
+        return self.adjusted_true_count(system.ace_betting_weight() * self.ace_surplus());
+    }
+
+    /// True count for the insurance decision, with the ace surplus folded
+    /// back in for systems that side count aces.
+    pub fn insurance_true_count(&self) -> f64 {
+        let system: &CountingSystem = self.counter.system();
+        if !system.has_ace_side_count() {
+            return self.true_count();
+        }
+        return self.adjusted_true_count(system.ace_insurance_weight() * self.ace_surplus());
+    }
+
+    fn adjusted_true_count(&self, adjustment : f64) -> f64 {
+        let decks_remaining: f64 = self.decks_remaining();
+        if decks_remaining <= 0.0 {
+            return self.running_count() + adjustment;
+        }
+        return (self.running_count() + adjustment) / decks_remaining;
+    }
+
+    pub fn display_count(&self) {
+        let system: &CountingSystem = self.counter.system();
+        if system.is_balanced() {
+            println!("{} running count: {:+}, true count: {:+.1} ({:.1} decks remaining)",
+                     system.name(),
+                     self.running_count(),
+                     self.true_count(),
+                     self.decks_remaining());
+        }
+        else {
+            println!("{} running count: {:+} ({:.1} decks remaining)", system.name(), self.running_count(), self.decks_remaining());
+        }
+        if system.has_ace_side_count() {
+            println!("Aces remaining: {} ({:+.1} against a normal shoe), betting true count: {:+.1}, insurance true count: {:+.1}",
+                     self.aces_remaining(),
+                     self.ace_surplus(),
+                     self.betting_true_count(),
+                     self.insurance_true_count());
+        }
+    }
+
+    /// Whether dealing to `num_of_player_hands` players will reset the shoe first.
+    pub fn will_reset_before_deal(&self, num_of_player_hands : usize) -> bool {
+        let num_of_cards_to_deal: usize = (num_of_player_hands + 1) * CARDS_PER_HAND as usize;
+        return num_of_cards_to_deal + self.cards_dealt() > self.penetration_depth.into() || self.cards.len() < num_of_cards_to_deal;
+    }
+
+    pub fn cards_remaining(&self) -> usize {
+        return self.cards.len();
+    }
+
+    /// Cards taken from the shoe since it was last reset, which sit in the discard tray.
+    pub fn cards_dealt(&self) -> usize {
+        return self.num_of_cards as usize - self.cards.len();
+    }
+
+    /// Cards dealt face up since the shoe was last reset, in the order they came out.
+    pub fn seen_cards(&self) -> &[char] {
+        return &self.seen_cards;
+    }
+
+    /// Deals one card face up, or `None` once the shoe is empty.
+    pub fn draw_card(&mut self) -> Option<char> {
+        if self.cards.is_empty() {
+            return None;
+        }
+        return Some(self.deal_one());
+    }
+
+    /// Takes a card out of play without it being seen or counted.
+    pub fn burn_card(&mut self) -> char {
+        return self.deal_face_down();
+    }
+
+    /// Counts the dealer's hole card once it has been turned over.
+    pub fn reveal_hole_card(&mut self) {
+        if let Some(card) = self.hole_card.take() {
+            self.see(card);
+        }
     }
 
     fn deal_one(&mut self) -> char {
-        if self.cards.len() == 0 {
+        let card: char = self.deal_face_down();
+        self.see(card);
+        return card;
+    }
+
+    fn see(&mut self, card : char) {
+        self.seen_cards.push(card);
+        self.counter.count(card);
+    }
+
+    fn deal_face_down(&mut self) -> char {
+        if self.cards.is_empty() {
             panic!("insufficient number of cards left");
         }
         return self.cards.pop().unwrap();
@@ -96,7 +251,7 @@ impl Shoe {
             panic!("total_num_of_cards < cards_left_in_shoe");
         }
 
-        let current_penetration_depth : usize = num_of_cards_to_deal as usize + (total_num_of_cards - cards_left_in_shoe);
+        let current_penetration_depth : usize = num_of_cards_to_deal + (total_num_of_cards - cards_left_in_shoe);
 
         println!("current penetration depth: {depth}", depth = current_penetration_depth);
 
@@ -104,7 +259,7 @@ impl Shoe {
             println!("Shoe penetration depth hit, resetting shoe");
             self.reset();
         }
-        else if self.cards.len() < num_of_cards_to_deal.into() {
+        else if self.cards.len() < num_of_cards_to_deal {
             println!("insufficient number of cards left, resetting shoe");
             self.reset();
         }
@@ -117,13 +272,18 @@ impl Shoe {
         let num_of_hands: usize = player_hands.len() + 1;
         let num_of_cards_to_deal : usize = num_of_hands * CARDS_PER_HAND as usize;
         
-        if self.check_penetration_depth(num_of_cards_to_deal) == false {
+        if !self.check_penetration_depth(num_of_cards_to_deal) {
             return false;
         }
 
-        // Deal the dealer's hand
+        // The last hole card was turned over at the end of its round.
+        self.reveal_hole_card();
+
+        // Deal the dealer's hand, hole card first
         dealer_hand.reset();
-        dealer_hand.add_card(self.deal_one());
+        let hole_card: char = self.deal_face_down();
+        self.hole_card = Some(hole_card);
+        dealer_hand.add_card(hole_card);
         dealer_hand.add_card(self.deal_one());
 
         // Deal the player hands
@@ -136,13 +296,15 @@ impl Shoe {
         return true;
     }
 
+    /// Deals a card to `hand`. A round in progress is dealt past the cut
+    /// card, since the shoe is only reshuffled between rounds.
     pub fn hit(&mut self, hand : &mut Hand) -> bool {
 
-        let num_of_cards_to_deal : usize = 1;
-        if self.check_penetration_depth(num_of_cards_to_deal) == false {
-            return false;
+        if self.cards.is_empty() {
+            println!("insufficient number of cards left, resetting shoe");
+            self.reset();
         }
-        
+
         return hand.add_card(self.deal_one());
     }
 }
@@ -172,6 +334,11 @@ mod shoe_tests {
         assert_eq!(shoe_2.cards.len(), 0);
         shoe_2.init();
         assert_eq!(shoe_2.cards.len(), 104);
+
+        let mut shoe_6 = Shoe::new( 6, 75, Vec::new());
+        shoe_6.init();
+        assert_eq!(shoe_6.cards.len(), 312);
+        assert_eq!(shoe_6.penetration_depth, 234);
     }
 
     #[test]
@@ -192,7 +359,7 @@ mod shoe_tests {
         shoe.init();
 
         let mut dealer_hand: Hand = Hand::new(Vec::new());
-        let mut hand: Hand = Hand::new(Vec::new());
+        let hand: Hand = Hand::new(Vec::new());
         let mut player_hands: Vec<Hand> = vec!(hand);
 
         for _n in 0..10 {
@@ -210,27 +377,100 @@ mod shoe_tests {
         shoe.init();
 
         let mut dealer_hand: Hand = Hand::new(Vec::new());
-        let mut hand: Hand = Hand::new(Vec::new());
+        let hand: Hand = Hand::new(Vec::new());
         let mut player_hands: Vec<Hand> = vec!(hand);
 
         shoe.deal(&mut player_hands, &mut dealer_hand);
         assert_eq!(shoe.cards.len(), 48);
     }
 
+    #[test]
+    fn shoe_counts_hole_card_when_revealed() {
+        let mut shoe = Shoe::new( 1, 100, vec!('5', '5', '5', '5', 'A', 'K'));
+
+        let mut dealer_hand: Hand = Hand::new(Vec::new());
+        let hand: Hand = Hand::new(Vec::new());
+        let mut player_hands: Vec<Hand> = vec!(hand);
+
+        shoe.deal(&mut player_hands, &mut dealer_hand);
+        assert_eq!(dealer_hand.cards, vec!('K', 'A'));
+        assert_eq!(shoe.running_count(), 1.0);
+        assert_eq!(shoe.decks_remaining(), 3.0 / 52.0);
+
+        shoe.reveal_hole_card();
+        assert_eq!(shoe.running_count(), 0.0);
+        assert_eq!(shoe.decks_remaining(), 2.0 / 52.0);
+
+        shoe.hit(&mut player_hands[0]);
+        assert_eq!(shoe.running_count(), 1.0);
+        assert_eq!(shoe.true_count(), 52.0);
+
+        shoe.reset();
+        assert_eq!(shoe.running_count(), 0.0);
+    }
+
+    #[test]
+    fn shoe_side_counts_aces() {
+        let mut shoe = Shoe::new( 1, 100, vec!('A', 'A', 'A', '7', '5', 'T', 'A', 'A'));
+        shoe.set_counting_system(CountingSystem::hi_opt_2());
+
+        let mut dealer_hand: Hand = Hand::new(Vec::new());
+        let hand: Hand = Hand::new(Vec::new());
+        let mut player_hands: Vec<Hand> = vec!(hand);
+
+        shoe.deal(&mut player_hands, &mut dealer_hand);
+        shoe.reveal_hole_card();
+        assert_eq!(shoe.seen_cards, vec!('A', 'T', '5', 'A'));
+        assert_eq!(shoe.aces_remaining(), 2);
+        assert_eq!(shoe.running_count(), 0.0);
+
+        // Four cards unseen should hold 4/13 of an ace: the shoe is ace rich.
+        let surplus: f64 = 2.0 - 4.0 / 13.0;
+        assert!((shoe.ace_surplus() - surplus).abs() < 1e-9);
+        assert!((shoe.betting_true_count() - 2.0 * surplus * 13.0).abs() < 1e-9);
+        assert_eq!(shoe.insurance_true_count(), shoe.true_count());
+
+        shoe.reset();
+        assert!(shoe.seen_cards.is_empty());
+        assert_eq!(shoe.aces_remaining(), 4);
+    }
+
     #[test]
     fn shoe_deal_until_penetration_depth() {
         let mut shoe = Shoe::new( 1, 50, Vec::new());
         shoe.init();
 
         let mut dealer_hand: Hand = Hand::new(Vec::new());
-        let mut hand: Hand = Hand::new(Vec::new());
+        let hand: Hand = Hand::new(Vec::new());
         let mut player_hands: Vec<Hand> = vec!(hand);
 
         for _n in 0..6 {
+            assert!(!shoe.will_reset_before_deal(player_hands.len()));
             assert!(shoe.deal(&mut player_hands, &mut dealer_hand));
         }
         assert_eq!(shoe.cards.len(), 28);
+        assert!(shoe.will_reset_before_deal(player_hands.len()));
         assert!(shoe.deal(&mut player_hands, &mut dealer_hand));
         assert_eq!(shoe.cards.len(), 48);
     }
+
+    #[test]
+    fn shoe_hits_past_the_cut_card() {
+        let mut shoe = Shoe::new( 1, 50, Vec::new());
+        shoe.init();
+
+        let mut dealer_hand: Hand = Hand::new(Vec::new());
+        let hand: Hand = Hand::new(Vec::new());
+        let mut player_hands: Vec<Hand> = vec!(hand);
+
+        for _n in 0..6 {
+            shoe.deal(&mut player_hands, &mut dealer_hand);
+        }
+        for _n in 0..4 {
+            shoe.hit(&mut dealer_hand);
+        }
+        assert_eq!(shoe.cards.len(), 24);
+        assert_eq!(shoe.num_of_shuffles(), 1);
+        assert!(shoe.will_reset_before_deal(player_hands.len()));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,148 @@
+use crate::game::action::UserAction;
+use crate::game::chart::{Chart, ChartAction, Section, UPCARDS, HARD_TOTALS, SOFT_TOTALS, PAIR_CARDS};
+use crate::game::composition::{Composition, NUM_OF_RANKS, ACE_RANK, rank_index, rank_value};
+use crate::game::dealer::DealerOdds;
+use crate::game::ev::{self, ActionEvs};
+use crate::game::rules::Rules;
+
+const RANK_CARDS : [char; NUM_OF_RANKS] = ['A', '2', '3', '4', '5', '6', '7', '8', '9', 'T'];
+
+/// Solves total-dependent basic strategy for a shoe of `num_of_decks` decks,
+/// or an infinite deck when `None`, by working out the EV of every action.
+///
+/// Each cell averages the EV over every two card hand that makes that row's
+/// total, weighted by how likely the hand is to be dealt against the upcard.
+pub fn solve(num_of_decks : Option<u8>, rules : &Rules) -> Chart {
+    let shoe: Composition = match num_of_decks {
+        Some(num_of_decks) => Composition::full_shoe(num_of_decks),
+        None => Composition::infinite_deck(),
+    };
+
+    let mut chart: Chart = Chart::new();
+    let mut dealer_odds: DealerOdds = DealerOdds::new();
+
+    for (column, upcard) in UPCARDS.iter().enumerate() {
+        let mut remaining: Composition = shoe.clone();
+        remaining.remove(rank_index(*upcard));
+
+        for (row, total) in HARD_TOTALS.iter().enumerate() {
+            let evs: ActionEvs = average_evs(&hard_hands(*total), *upcard, &remaining, false, rules, &mut dealer_odds);
+            chart.set(Section::Hard, row, column, chart_action(&evs));
+        }
+
+        for (row, total) in SOFT_TOTALS.iter().enumerate() {
+            let hand: Vec<char> = vec!['A', RANK_CARDS[(total - 11 - 1) as usize]];
+            let evs: ActionEvs = average_evs(&[hand], *upcard, &remaining, false, rules, &mut dealer_odds);
+            chart.set(Section::Soft, row, column, chart_action(&evs));
+        }
+
+        for (row, card) in PAIR_CARDS.iter().enumerate() {
+            let evs: ActionEvs = average_evs(&[vec![*card, *card]], *upcard, &remaining, true, rules, &mut dealer_odds);
+            chart.set(Section::Pairs, row, column, chart_action(&evs));
+        }
+    }
+    return chart;
+}
+
+/// Every two card hand without an ace that totals `total`, pairs included.
+fn hard_hands(total : u8) -> Vec<Vec<char>> {
+    let mut hands: Vec<Vec<char>> = Vec::new();
+    for (first, first_card) in RANK_CARDS.iter().enumerate().skip(ACE_RANK + 1) {
+        for (second, second_card) in RANK_CARDS.iter().enumerate().skip(first) {
+            if rank_value(first) + rank_value(second) == total {
+                hands.push(vec![*first_card, *second_card]);
+            }
+        }
+    }
+    return hands;
+}
+
+/// Probability weighted average of each action's EV over `hands`.
+fn average_evs(hands : &[Vec<char>],
+               upcard : char,
+               composition : &Composition,
+               allow_split : bool,
+               rules : &Rules,
+               dealer_odds : &mut DealerOdds) -> ActionEvs {
+    let mut total_weight: f64 = 0.0;
+    let mut average: ActionEvs = ActionEvs {
+        hit         : 0.0,
+        stand       : 0.0,
+        double_down : Some(0.0),
+        split       : if allow_split { Some(0.0) } else { None },
+        surrender   : if rules.late_surrender { Some(0.0) } else { None },
+    };
+
+    for hand in hands {
+        let mut remaining: Composition = composition.clone();
+        let first_probability: f64 = remaining.probability(rank_index(hand[0]));
+        remaining.remove(rank_index(hand[0]));
+        let second_probability: f64 = remaining.probability(rank_index(hand[1]));
+        remaining.remove(rank_index(hand[1]));
+
+        let mut weight: f64 = first_probability * second_probability;
+        if hand[0] != hand[1] {
+            weight *= 2.0;
+        }
+        if weight == 0.0 {
+            continue;
+        }
+
+        let evs: ActionEvs = ev::evaluate(hand, false, upcard, &remaining, rules, dealer_odds);
+        total_weight += weight;
+        average.hit += weight * evs.hit;
+        average.stand += weight * evs.stand;
+        average.double_down = average.double_down.map(|ev| ev + weight * evs.double_down.unwrap_or(0.0));
+        average.split = average.split.map(|ev| ev + weight * evs.split.unwrap_or(0.0));
+        average.surrender = average.surrender.map(|ev| ev + weight * evs.surrender.unwrap_or(0.0));
+    }
+
+    average.hit /= total_weight;
+    average.stand /= total_weight;
+    average.double_down = average.double_down.map(|ev| ev / total_weight);
+    average.split = average.split.map(|ev| ev / total_weight);
+    average.surrender = average.surrender.map(|ev| ev / total_weight);
+    return average;
+}
+
+fn chart_action(evs : &ActionEvs) -> ChartAction {
+    let hit_or_stand: UserAction = evs.best_among(&[UserAction::Hit]).0;
+    match evs.best().0 {
+        UserAction::DoubleDown => {
+            return if hit_or_stand == UserAction::Hit { ChartAction::DoubleOrHit } else { ChartAction::DoubleOrStand };
+        },
+        UserAction::Split => return ChartAction::Split,
+        UserAction::Surrender => {
+            if evs.best_among(&[UserAction::Hit, UserAction::Split]).0 == UserAction::Split {
+                return ChartAction::SurrenderOrSplit;
+            }
+            return if hit_or_stand == UserAction::Hit { ChartAction::SurrenderOrHit } else { ChartAction::SurrenderOrStand };
+        },
+        UserAction::Hit => return ChartAction::Hit,
+        _=> return ChartAction::Stand,
+    }
+}
+
+
+#[cfg(test)]
+mod solver_tests {
+    use super::*;
+
+    #[test]
+    fn solver_reproduces_published_basic_strategy() {
+        // The published four to eight deck chart for S17, DAS and late surrender.
+        let solved = solve(Some(6), &Rules::default());
+        assert_eq!(solved, Chart::basic_strategy());
+    }
+
+    #[test]
+    fn solver_hitting_soft_17_changes_the_chart() {
+        let rules = Rules { dealer_hits_soft_17 : true, ..Rules::default() };
+        let solved = solve(None, &rules);
+        assert_eq!(solved.lookup(&['6', '5'], 'A', true), ChartAction::DoubleOrHit);
+        assert_eq!(solved.lookup(&['A', '8'], '6', true), ChartAction::DoubleOrStand);
+        assert_eq!(solved.lookup(&['T', '5'], 'A', true), ChartAction::SurrenderOrHit);
+        assert_eq!(solved.lookup(&['T', '7'], 'A', true), ChartAction::SurrenderOrStand);
+        assert_eq!(solved.lookup(&['8', '8'], 'A', true), ChartAction::SurrenderOrSplit);
+    }
+}
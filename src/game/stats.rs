@@ -0,0 +1,216 @@
+use crate::game::action::UserAction;
+use crate::game::chart::Section;
+use crate::game::quiz::QuizScore;
+
+/// Mistakes shown at the end of a session, costliest first, and likewise
+/// the slowest timed answers.
+const MISTAKES_SHOWN : usize = 5;
+const SLOWEST_SHOWN  : usize = 5;
+
+/// The kind of decision a play was, for accuracy by category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecisionCategory {
+    Hard,
+    Soft,
+    Pair,
+    Surrender,
+    Deviation,
+}
+
+const CATEGORIES : [DecisionCategory; 5] = [DecisionCategory::Hard,
+                                            DecisionCategory::Soft,
+                                            DecisionCategory::Pair,
+                                            DecisionCategory::Surrender,
+                                            DecisionCategory::Deviation];
+
+impl DecisionCategory {
+    /// Index plays count as deviations and surrender plays, taken or
+    /// missed, as surrenders. Everything else goes by the chart section,
+    /// with hands the chart doesn't list counted as hard.
+    pub fn of(section : Option<Section>, deviation : bool, action : UserAction, correct_action : UserAction) -> DecisionCategory {
+        if deviation {
+            return DecisionCategory::Deviation;
+        }
+        if action == UserAction::Surrender || correct_action == UserAction::Surrender {
+            return DecisionCategory::Surrender;
+        }
+        match section {
+            Some(Section::Soft)  => return DecisionCategory::Soft,
+            Some(Section::Pairs) => return DecisionCategory::Pair,
+            _=> return DecisionCategory::Hard,
+        }
+    }
+
+    fn index(&self) -> usize {
+        return CATEGORIES.iter().position(|category| category == self).unwrap();
+    }
+}
+
+/// One graded play. `cost` is the EV given up against the correct action,
+/// and `seconds` how long the answer took when decisions are timed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    pub situation      : String,
+    pub action         : UserAction,
+    pub correct_action : UserAction,
+    pub cost           : f64,
+    pub seconds        : Option<f64>,
+    pub timed_out      : bool,
+}
+
+impl Decision {
+    /// An answer past the time limit is a mistake even if the action was right.
+    pub fn is_correct(&self) -> bool {
+        return self.action == self.correct_action && !self.timed_out;
+    }
+}
+
+/// Running totals for a playing session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionStats {
+    hands      : u32,
+    decisions  : QuizScore,
+    categories : [QuizScore; CATEGORIES.len()],
+    net        : f64,
+    plays      : Vec<Decision>,
+}
+
+impl SessionStats {
+    pub fn new() -> SessionStats {
+        return SessionStats::default();
+    }
+
+    /// Records a round of `hands` settled hands that won `winnings` units.
+    pub fn record_round(&mut self, hands : u32, winnings : f64) {
+        self.hands += hands;
+        self.net += winnings;
+    }
+
+    pub fn record_decision(&mut self, category : DecisionCategory, decision : Decision) {
+        self.decisions.record(decision.is_correct());
+        self.categories[category.index()].record(decision.is_correct());
+        self.plays.push(decision);
+    }
+
+    pub fn category(&self, category : DecisionCategory) -> QuizScore {
+        return self.categories[category.index()];
+    }
+
+    /// The `count` costliest mistakes of the session.
+    pub fn biggest_mistakes(&self, count : usize) -> Vec<&Decision> {
+        let mut mistakes: Vec<&Decision> = self.plays.iter().filter(|play| !play.is_correct()).collect();
+        mistakes.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+        mistakes.truncate(count);
+        return mistakes;
+    }
+
+    /// The `count` timed plays that took longest to answer.
+    pub fn slowest_decisions(&self, count : usize) -> Vec<&Decision> {
+        let mut timed: Vec<&Decision> = self.plays.iter().filter(|play| play.seconds.is_some()).collect();
+        timed.sort_by(|a, b| b.seconds.unwrap_or(0.0).total_cmp(&a.seconds.unwrap_or(0.0)));
+        timed.truncate(count);
+        return timed;
+    }
+
+    pub fn display(&self) {
+        println!("{} hands, {} decisions, {:.0}% correct, net {:+} units",
+                 self.hands,
+                 self.decisions.asked,
+                 self.decisions.accuracy() * 100.0,
+                 self.net);
+        for category in CATEGORIES {
+            let score: QuizScore = self.category(category);
+            if score.asked > 0 {
+                println!("  {:?}: {}/{} ({:.0}%)", category, score.correct, score.asked, score.accuracy() * 100.0);
+            }
+        }
+
+        let mistakes: Vec<&Decision> = self.biggest_mistakes(MISTAKES_SHOWN);
+        if !mistakes.is_empty() {
+            println!("Biggest mistakes:");
+        }
+        for mistake in mistakes {
+            if mistake.action == mistake.correct_action {
+                println!("  {}: {:?} too slowly, {:.1}s", mistake.situation, mistake.action, mistake.seconds.unwrap_or(0.0));
+            }
+            else {
+                println!("  {}: {:?} instead of {:?}, cost {:.4} units", mistake.situation, mistake.action, mistake.correct_action, mistake.cost);
+            }
+        }
+
+        let slowest: Vec<&Decision> = self.slowest_decisions(SLOWEST_SHOWN);
+        if !slowest.is_empty() {
+            let timed: Vec<f64> = self.plays.iter().filter_map(|play| play.seconds).collect();
+            let timeouts: usize = self.plays.iter().filter(|play| play.timed_out).count();
+            println!("Average decision time {:.1}s, {} too slow", timed.iter().sum::<f64>() / timed.len() as f64, timeouts);
+            println!("Slowest decisions:");
+        }
+        for decision in slowest {
+            println!("  {}: {:.1}s{}", decision.situation, decision.seconds.unwrap_or(0.0), if decision.timed_out { " (too slow)" } else { "" });
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod stats_tests {
+    use super::*;
+
+    #[test]
+    fn decision_categories() {
+        assert_eq!(DecisionCategory::of(Some(Section::Hard), true, UserAction::Stand, UserAction::Stand), DecisionCategory::Deviation);
+        assert_eq!(DecisionCategory::of(Some(Section::Hard), false, UserAction::Hit, UserAction::Surrender), DecisionCategory::Surrender);
+        assert_eq!(DecisionCategory::of(Some(Section::Soft), false, UserAction::Hit, UserAction::Stand), DecisionCategory::Soft);
+        assert_eq!(DecisionCategory::of(Some(Section::Pairs), false, UserAction::Split, UserAction::Split), DecisionCategory::Pair);
+        assert_eq!(DecisionCategory::of(None, false, UserAction::Stand, UserAction::Stand), DecisionCategory::Hard);
+    }
+
+    fn decision(situation : &str, action : UserAction, correct_action : UserAction, cost : f64, seconds : Option<f64>, timed_out : bool) -> Decision {
+        return Decision {
+            situation : situation.to_string(),
+            action,
+            correct_action,
+            cost,
+            seconds,
+            timed_out,
+        };
+    }
+
+    #[test]
+    fn session_totals() {
+        let mut stats = SessionStats::new();
+        stats.record_round(1, -1.0);
+        stats.record_round(2, 3.0);
+        stats.record_decision(DecisionCategory::Hard, decision("T 6 vs T", UserAction::Stand, UserAction::Surrender, 0.04, None, false));
+        stats.record_decision(DecisionCategory::Hard, decision("T 2 vs 2", UserAction::Hit, UserAction::Hit, 0.0, None, false));
+        stats.record_decision(DecisionCategory::Soft, decision("A 7 vs 9", UserAction::Stand, UserAction::Hit, 0.1, None, false));
+
+        assert_eq!(stats.hands, 3);
+        assert_eq!(stats.net, 2.0);
+        assert_eq!(stats.decisions, QuizScore { asked : 3, correct : 1 });
+        assert_eq!(stats.category(DecisionCategory::Hard), QuizScore { asked : 2, correct : 1 });
+        assert_eq!(stats.category(DecisionCategory::Pair), QuizScore::default());
+
+        let mistakes = stats.biggest_mistakes(5);
+        assert_eq!(mistakes.len(), 2);
+        assert_eq!(mistakes[0].situation, "A 7 vs 9");
+        assert_eq!(stats.biggest_mistakes(1).len(), 1);
+    }
+
+    #[test]
+    fn timeouts_are_mistakes() {
+        let mut stats = SessionStats::new();
+        stats.record_decision(DecisionCategory::Hard, decision("T 6 vs T", UserAction::Surrender, UserAction::Surrender, 0.0, Some(7.5), true));
+        stats.record_decision(DecisionCategory::Pair, decision("8 8 vs A", UserAction::Split, UserAction::Split, 0.0, Some(2.0), false));
+        stats.record_decision(DecisionCategory::Soft, decision("A 7 vs 9", UserAction::Hit, UserAction::Hit, 0.0, Some(3.25), false));
+        stats.record_decision(DecisionCategory::Hard, decision("T 2 vs 2", UserAction::Hit, UserAction::Hit, 0.0, None, false));
+
+        assert_eq!(stats.decisions, QuizScore { asked : 4, correct : 3 });
+        assert_eq!(stats.category(DecisionCategory::Hard), QuizScore { asked : 2, correct : 1 });
+        assert_eq!(stats.biggest_mistakes(5)[0].situation, "T 6 vs T");
+
+        let slowest: Vec<&str> = stats.slowest_decisions(2).iter().map(|decision| decision.situation.as_str()).collect();
+        assert_eq!(slowest, ["T 6 vs T", "A 7 vs 9"]);
+        assert_eq!(stats.slowest_decisions(10).len(), 3);
+    }
+}
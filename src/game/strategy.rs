@@ -0,0 +1,80 @@
+use crate::game::action::UserAction;
+use crate::game::composition::{Composition, rank_index};
+use crate::game::dealer::DealerOdds;
+use crate::game::ev::{self, ActionEvs};
+use crate::game::rules::Rules;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategyMode {
+    /// Decisions depend only on the player's total and the dealer upcard.
+    TotalDependent,
+    /// Decisions depend on the exact cards in the player's hand.
+    CompositionDependent,
+}
+
+/// Composition-dependent basic strategy: the best of `actions` for the exact
+/// cards in `player_cards`, drawing from a freshly shuffled shoe of
+/// `num_of_decks` decks with the player's cards and the upcard removed.
+/// `split_hand` says the hand came from a split.
+pub fn composition_dependent_strategy(player_cards : &[char],
+                                      split_hand : bool,
+                                      upcard : char,
+                                      num_of_decks : u8,
+                                      actions : &[UserAction],
+                                      rules : &Rules,
+                                      dealer_odds : &mut DealerOdds) -> UserAction {
+    let mut composition: Composition = Composition::full_shoe(num_of_decks);
+    for card in player_cards {
+        composition.remove(rank_index(*card));
+    }
+    composition.remove(rank_index(upcard));
+
+    let mut action_evs: ActionEvs = ev::evaluate(player_cards, split_hand, upcard, &composition, rules, dealer_odds);
+    action_evs.restrict(actions);
+    return action_evs.best().0;
+}
+
+/// Hitting and standing plus whichever of the other actions the hand allows.
+pub fn available_actions(can_double : bool, can_split : bool, can_surrender : bool) -> Vec<UserAction> {
+    let mut actions: Vec<UserAction> = vec![UserAction::Hit, UserAction::Stand];
+    if can_double {
+        actions.push(UserAction::DoubleDown);
+    }
+    if can_split {
+        actions.push(UserAction::Split);
+    }
+    if can_surrender {
+        actions.push(UserAction::Surrender);
+    }
+    return actions;
+}
+
+#[cfg(test)]
+mod strategy_tests {
+    use super::*;
+
+    fn cd_play(player_cards : &[char], upcard : char, num_of_decks : u8) -> UserAction {
+        let actions: Vec<UserAction> = available_actions(true, false, false);
+        return composition_dependent_strategy(player_cards, false, upcard, num_of_decks, &actions, &Rules::default(), &mut DealerOdds::new());
+    }
+
+    #[test]
+    fn strategy_composition_dependent_sixteen() {
+        // Multi-card 16 against a ten stands, two card 16 hits.
+        assert_eq!(cd_play(&['T', '6'], 'T', 1), UserAction::Hit);
+        assert_eq!(cd_play(&['4', '4', '4', '4'], 'T', 1), UserAction::Stand);
+    }
+
+    #[test]
+    fn strategy_composition_dependent_twelve() {
+        // Single deck 12 against 4: ten-two hits while seven-five stands.
+        assert_eq!(cd_play(&['T', '2'], '4', 1), UserAction::Hit);
+        assert_eq!(cd_play(&['7', '5'], '4', 1), UserAction::Stand);
+    }
+
+    #[test]
+    fn strategy_available_actions() {
+        assert_eq!(available_actions(false, false, false), vec![UserAction::Hit, UserAction::Stand]);
+        assert_eq!(available_actions(true, true, true).len(), 5);
+    }
+}
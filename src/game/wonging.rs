@@ -0,0 +1,212 @@
+use crate::game::action::UserAction;
+use crate::game::chart::Chart;
+use crate::game::count::CountingSystem;
+use crate::game::hand::Hand;
+use crate::game::rules::Rules;
+use crate::game::shoe::Shoe;
+
+const BLACKJACK : u8 = 21;
+
+/// When to join a game that is being back counted and when to leave it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WongRule {
+    pub enter : f64,
+    pub exit  : f64,
+}
+
+impl WongRule {
+    /// Enter at `enter` or more, and once seated stay until the true count
+    /// drops below `exit`.
+    pub fn new(enter : f64, exit : f64) -> WongRule {
+        WongRule {
+            enter,
+            exit,
+        }
+    }
+
+    pub fn should_play(&self, seated : bool, true_count : f64) -> bool {
+        if seated {
+            return true_count >= self.exit;
+        }
+        return true_count >= self.enter;
+    }
+
+    pub fn describe(&self) -> String {
+        return format!("in at {:+}, out below {:+}", self.enter, self.exit);
+    }
+}
+
+/// Totals over a simulated run of shoes for one way of wonging.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SimulationResult {
+    pub rounds_seen   : u32,
+    pub rounds_played : u32,
+    pub units_won     : f64,
+}
+
+impl SimulationResult {
+    pub fn win_per_100_played(&self) -> f64 {
+        if self.rounds_played == 0 {
+            return 0.0;
+        }
+        return self.units_won * 100.0 / self.rounds_played as f64;
+    }
+
+    pub fn win_per_100_seen(&self) -> f64 {
+        if self.rounds_seen == 0 {
+            return 0.0;
+        }
+        return self.units_won * 100.0 / self.rounds_seen as f64;
+    }
+}
+
+/// Deals `num_of_shoes` shoes one seat at a time with the seat played by
+/// `chart` and flat one unit bets. With a `rule` rounds are only bet when
+/// the rule says so, judged by the betting true count of `system` as in
+/// play; without one every round is bet.
+pub fn simulate(rule : Option<WongRule>,
+                num_of_shoes : u32,
+                num_of_decks : u8,
+                penetration_percentage : u8,
+                system : &CountingSystem,
+                chart : &Chart,
+                rules : &Rules) -> SimulationResult {
+    let mut result: SimulationResult = SimulationResult::default();
+    let mut shoe: Shoe = Shoe::new(num_of_decks, penetration_percentage, Vec::new());
+    shoe.set_counting_system(system.clone());
+
+    for _shoe in 0..num_of_shoes {
+        shoe.reset();
+        let mut seated: bool = rule.is_none();
+
+        while !shoe.will_reset_before_deal(1) {
+            seated = rule.is_none_or(|rule| rule.should_play(seated, shoe.betting_true_count()));
+            let units: f64 = play_round(&mut shoe, chart, rules);
+
+            result.rounds_seen += 1;
+            if seated {
+                result.rounds_played += 1;
+                result.units_won += units;
+            }
+        }
+    }
+    return result;
+}
+
+/// Plays one round for one seat without any output, returning what a one
+/// unit bet won. Split hands can't be split again.
+pub fn play_round(shoe : &mut Shoe, chart : &Chart, rules : &Rules) -> f64 {
+    let mut dealer_hand: Hand = Hand::new(Vec::new());
+    let mut player_hand: Hand = Hand::new(Vec::new());
+    dealer_hand.add_card(draw(shoe));
+    let upcard: char = draw(shoe);
+    dealer_hand.add_card(upcard);
+    player_hand.add_card(draw(shoe));
+    player_hand.add_card(draw(shoe));
+
+    if dealer_hand.is_natural() {
+        return if player_hand.is_natural() { 0.0 } else { -1.0 };
+    }
+    if player_hand.is_natural() {
+        return 1.5;
+    }
+
+    let mut hands: Vec<(Hand, f64)> = Vec::new();
+    let first_action: UserAction = chart.lookup(&player_hand.cards, upcard, true).resolve(true, rules.late_surrender);
+    match first_action {
+        UserAction::Surrender => return -0.5,
+        UserAction::Split => {
+            let second_hand: Hand = player_hand.split();
+            for mut hand in [player_hand, second_hand] {
+                hand.add_card(draw(shoe));
+                let bet: f64 = if hand.cards[0] == 'A' { 1.0 } else { play_out(shoe, &mut hand, upcard, chart, rules.double_after_split) };
+                hands.push((hand, bet));
+            }
+        },
+        UserAction::DoubleDown => {
+            player_hand.add_card(draw(shoe));
+            hands.push((player_hand, 2.0));
+        },
+        UserAction::Hit => {
+            if player_hand.add_card(draw(shoe)) {
+                play_out(shoe, &mut player_hand, upcard, chart, false);
+            }
+            hands.push((player_hand, 1.0));
+        },
+        _=> hands.push((player_hand, 1.0)),
+    }
+
+    if hands.iter().any(|(hand, _bet)| hand.total() <= BLACKJACK) {
+        while dealer_hand.total() < 17 || (dealer_hand.total() == 17 && dealer_hand.is_soft() && rules.dealer_hits_soft_17) {
+            dealer_hand.add_card(draw(shoe));
+        }
+    }
+
+    let dealer_total: u8 = dealer_hand.total();
+    let mut units: f64 = 0.0;
+    for (hand, bet) in hands.iter() {
+        let player_total: u8 = hand.total();
+        if player_total > BLACKJACK || (dealer_total <= BLACKJACK && dealer_total > player_total) {
+            units -= bet;
+        }
+        else if dealer_total > BLACKJACK || player_total > dealer_total {
+            units += bet;
+        }
+    }
+    return units;
+}
+
+/// Hits, stands or doubles a hand by the chart, returning the bet on it.
+fn play_out(shoe : &mut Shoe, hand : &mut Hand, upcard : char, chart : &Chart, can_double : bool) -> f64 {
+    loop {
+        let action: UserAction = chart.lookup(&hand.cards, upcard, false).resolve(can_double && hand.cards.len() == 2, false);
+        match action {
+            UserAction::Hit => {
+                if !hand.add_card(draw(shoe)) {
+                    return 1.0;
+                }
+            },
+            UserAction::DoubleDown => {
+                hand.add_card(draw(shoe));
+                return 2.0;
+            },
+            _=> return 1.0,
+        }
+    }
+}
+
+fn draw(shoe : &mut Shoe) -> char {
+    return shoe.draw_card().expect("the shoe ran out of cards in the middle of a round");
+}
+
+
+#[cfg(test)]
+mod wonging_tests {
+    use super::*;
+
+    #[test]
+    fn wong_rule_decisions() {
+        let rule = WongRule::new(2.0, 1.0);
+        assert!(!rule.should_play(false, 1.5));
+        assert!(rule.should_play(false, 2.0));
+        assert!(rule.should_play(true, 1.5));
+        assert!(!rule.should_play(true, 0.9));
+    }
+
+    #[test]
+    fn simulation_plays_only_when_wonged_in() {
+        let chart = Chart::basic_strategy();
+        let rules = Rules::default();
+
+        let every_round = simulate(None, 20, 6, 75, &CountingSystem::hi_lo(), &chart, &rules);
+        assert!(every_round.rounds_seen > 20 * 30);
+        assert_eq!(every_round.rounds_played, every_round.rounds_seen);
+
+        let never = simulate(Some(WongRule::new(100.0, 100.0)), 20, 6, 75, &CountingSystem::hi_lo(), &chart, &rules);
+        assert_eq!(never.rounds_played, 0);
+        assert_eq!(never.units_won, 0.0);
+
+        let wonging = simulate(Some(WongRule::new(2.0, 1.0)), 20, 6, 75, &CountingSystem::hi_lo(), &chart, &rules);
+        assert!(wonging.rounds_played < wonging.rounds_seen);
+    }
+}
@@ -0,0 +1,109 @@
+use std::io::{stdin, stdout, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::game::action::UserAction;
+
+const ACTION_PROMPT : &str = "(H)it, (S)tand, (D)ouble Down, S(p)lit, or Su(r)render: ";
+
+/// Set once input has been closed, after which every read is an empty line.
+static INPUT_CLOSED: AtomicBool = AtomicBool::new(false);
+
+pub fn get_user_action() -> UserAction {
+    print!("{}", ACTION_PROMPT);
+    return user_input_to_action(&read_user_input());
+}
+
+/// Reads an action, warning once `time_limit` passes without one, and
+/// returns it with how long the answer took.
+pub fn get_timed_user_action(time_limit : Duration) -> (UserAction, Duration) {
+    print!("{}", ACTION_PROMPT);
+    let (input, elapsed) = read_timed_user_input(time_limit);
+    return (user_input_to_action(&input), elapsed);
+}
+
+pub fn user_input_to_action(input: &str) -> UserAction {
+    // let lowercase_input: String = input.to_lowercase();
+    match input {
+        "h" => return UserAction::Hit,
+        "s" => return UserAction::Stand,
+        "d" => return UserAction::DoubleDown,
+        "p" => return UserAction::Split,
+        "r" => return UserAction::Surrender,
+        _=>    return UserAction::Unknown,
+    }
+}
+
+/// Reads a number, or `None` if the answer isn't one.
+pub fn get_user_number(prompt : &str) -> Option<f64> {
+    print!("{}", prompt);
+    return read_user_input().trim().parse().ok();
+}
+
+/// Reads a yes or no answer; anything but `y` is a no.
+pub fn get_user_confirmation(prompt : &str) -> bool {
+    print!("{}", prompt);
+    return read_user_input().trim() == "y";
+}
+
+pub fn read_user_input() -> String {
+    let _=stdout().flush();
+    let s: String = next_line(input_lines().lock().unwrap().recv().ok());
+    println!("You typed: {}",s);
+    return s;
+}
+
+/// Like `read_user_input`, but says so when `time_limit` passes. The answer
+/// is still waited for, so nothing typed late spills into the next prompt.
+fn read_timed_user_input(time_limit : Duration) -> (String, Duration) {
+    let _=stdout().flush();
+    let started: Instant = Instant::now();
+    let lines = input_lines().lock().unwrap();
+    let s: String = match lines.recv_timeout(time_limit) {
+        Ok(line) => line,
+        Err(RecvTimeoutError::Timeout) => {
+            print!("\nTime's up! ");
+            let _=stdout().flush();
+            next_line(lines.recv().ok())
+        },
+        Err(RecvTimeoutError::Disconnected) => next_line(None),
+    };
+    let elapsed: Duration = started.elapsed();
+    println!("You typed: {}",s);
+    return (s, elapsed);
+}
+
+/// Whether input has been closed, so no answer will ever come.
+pub fn input_closed() -> bool {
+    return INPUT_CLOSED.load(Ordering::Relaxed);
+}
+
+fn next_line(line : Option<String>) -> String {
+    match line {
+        Some(line) => return line,
+        None => {
+            INPUT_CLOSED.store(true, Ordering::Relaxed);
+            return String::new();
+        },
+    }
+}
+
+/// Lines typed at the terminal, read on a thread of their own so a timed
+/// answer can stop waiting without blocking on stdin.
+fn input_lines() -> &'static Mutex<Receiver<String>> {
+    static LINES: OnceLock<Mutex<Receiver<String>>> = OnceLock::new();
+    return LINES.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in stdin().lock().lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    return;
+                }
+            }
+        });
+        Mutex::new(receiver)
+    });
+}
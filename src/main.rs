@@ -1,23 +1,75 @@
 
-
+use crate::drill::aces;
+use crate::drill::countdown::{self, CountdownResult, CountdownSettings};
+use crate::drill::curriculum::{self, Lesson, LESSONS};
+use crate::drill::discard;
+use crate::drill::flash::{self, HandFilter};
+use crate::drill::totals;
+use crate::game::action::UserAction;
+use crate::game::betting::{self, BetRamp};
+use crate::game::chart::{self, Chart};
+use crate::game::composition::{Composition, TEN_RANK, rank_index};
+use crate::game::count::CountingSystem;
+use crate::game::dealer::DealerOdds;
+use crate::game::deviation::{Condition, Deviation, IndexTable, HI_LO_INSURANCE};
+use crate::game::distraction::{Distraction, Distractions};
+use crate::game::ev::{self, ActionEvs};
+use crate::game::explain;
 use crate::game::hand::Hand;
+use crate::game::profile::Profile;
+use crate::game::quiz::{CountQuiz, QuizPoint, QuizScore};
+use crate::game::render::{self, CellCounts, ChartFormat};
+use crate::game::review::{self, ReviewSchedule};
+use crate::game::rules::Rules;
 use crate::game::shoe::Shoe;
+use crate::game::solver;
+use crate::game::stats::{Decision, DecisionCategory, SessionStats};
+use crate::game::strategy::{self, StrategyMode};
+use crate::game::wonging::{self, SimulationResult, WongRule};
+use crate::input::{get_timed_user_action, get_user_action, get_user_confirmation, get_user_number, input_closed, read_user_input};
 
+mod drill;
 mod game;
+mod input;
 
-use core::num;
-use std::cmp;
-use std::thread::{sleep, self};
+use std::env;
+use std::fs;
+use std::thread;
 use std::time::Duration;
 
 
 
+const DEFAULT_BANKROLL : f64 = 100.0;
+
 #[derive(Debug)]
 pub struct Game {
     num_of_players : u8,
     shoe         : Shoe,
     dealer_hand  : Hand,
     player_hands : Vec<Hand>,
+    rules        : Rules,
+    dealer_odds  : DealerOdds,
+    shoe_shuffles: u32,
+    strategy_mode: StrategyMode,
+    chart        : Chart,
+    mistakes     : CellCounts,
+    count_quiz   : Option<CountQuiz>,
+    index_table  : Option<IndexTable>,
+    bankroll     : f64,
+    bets         : Vec<f64>,
+    bet_ramp     : Option<BetRamp>,
+    bet_score    : QuizScore,
+    kelly        : bool,
+    wong_rule    : Option<WongRule>,
+    seated       : bool,
+    wong_score   : QuizScore,
+    insurance_score: QuizScore,
+    review_schedule: ReviewSchedule,
+    review_file  : Option<String>,
+    stats        : SessionStats,
+    profile      : Option<Profile>,
+    time_limit   : Option<Duration>,
+    distractions : Option<Distractions>,
 }
 
 impl Game {
@@ -25,97 +77,603 @@ impl Game {
         let mut shoe: Shoe = Shoe::new(num_of_decks, penetration_percentage, Vec::new());
         shoe.init();
 
-        let mut dealer_hand: Hand = Hand::new(Vec::new());
+        let dealer_hand: Hand = Hand::new(Vec::new());
         let mut player_hands: Vec<Hand> = Vec::new();
-        for player in 0..num_of_players {
+        for _player in 0..num_of_players {
             player_hands.push(Hand::new(Vec::new()));
         }
 
         Game {
-            num_of_players : num_of_players,
-            shoe           : shoe,
-            dealer_hand    : dealer_hand,
-            player_hands   : player_hands,
+            num_of_players,
+            shoe,
+            dealer_hand,
+            player_hands,
+            rules : Rules::default(),
+            dealer_odds : DealerOdds::new(),
+            shoe_shuffles : 0,
+            strategy_mode : StrategyMode::TotalDependent,
+            chart : Chart::basic_strategy(),
+            mistakes : CellCounts::new(),
+            count_quiz : None,
+            index_table : None,
+            bankroll : DEFAULT_BANKROLL,
+            bets : Vec::new(),
+            bet_ramp : None,
+            bet_score : QuizScore::default(),
+            kelly : false,
+            wong_rule : None,
+            seated : true,
+            wong_score : QuizScore::default(),
+            insurance_score : QuizScore::default(),
+            review_schedule : ReviewSchedule::new(),
+            review_file : None,
+            stats : SessionStats::new(),
+            profile : None,
+            time_limit : None,
+            distractions : None,
+        }
+    }
+
+    /// Loads the review schedule from `path` and saves every graded play back to it.
+    pub fn set_review_file(&mut self, path : &str) {
+        self.review_schedule = ReviewSchedule::load(path);
+        self.review_file = Some(path.to_string());
+    }
+
+    /// Plays as `profile`: its bankroll and review schedule carry over,
+    /// and every graded play is added to its history.
+    pub fn set_profile(&mut self, profile : Profile) {
+        if let Some(bankroll) = profile.bankroll {
+            self.bankroll = bankroll;
+        }
+        self.set_review_file(&profile.file(review::REVIEW_FILE));
+        self.profile = Some(profile);
+    }
+
+    /// Times every decision; answers slower than `time_limit` are graded wrong.
+    pub fn set_time_limit(&mut self, time_limit : Duration) {
+        self.time_limit = Some(time_limit);
+    }
+
+    /// Interrupts play with table talk, some of it questions to answer.
+    pub fn set_distractions(&mut self, distractions : Distractions) {
+        self.distractions = Some(distractions);
+    }
+
+    pub fn set_strategy_mode(&mut self, strategy_mode : StrategyMode) {
+        self.strategy_mode = strategy_mode;
+    }
+
+    pub fn set_rules(&mut self, rules : Rules) {
+        self.rules = rules;
+    }
+
+    pub fn set_chart(&mut self, chart : Chart) {
+        self.chart = chart;
+    }
+
+    pub fn set_counting_system(&mut self, system : CountingSystem) {
+        self.shoe.set_counting_system(system);
+    }
+
+    pub fn set_count_quiz(&mut self, count_quiz : CountQuiz) {
+        self.count_quiz = Some(count_quiz);
+    }
+
+    pub fn set_index_table(&mut self, index_table : IndexTable) {
+        self.index_table = Some(index_table);
+    }
+
+    pub fn set_bankroll(&mut self, bankroll : f64) {
+        self.bankroll = bankroll;
+    }
+
+    pub fn set_bet_ramp(&mut self, bet_ramp : BetRamp) {
+        self.bet_ramp = Some(bet_ramp);
+    }
+
+    /// Suggests the Kelly bet for the estimated advantage before each round.
+    pub fn set_kelly(&mut self, kelly : bool) {
+        self.kelly = kelly;
+    }
+
+    /// Back counts the table, asking before each round whether to play it.
+    pub fn set_wong_rule(&mut self, wong_rule : WongRule) {
+        self.wong_rule = Some(wong_rule);
+        self.seated = false;
+    }
+
+    /// Plays hands until the user quits, offering other commands between hands.
+    pub fn run(&mut self) {
+        loop {
+            // Each seat needs at least a unit to bet.
+            if self.bankroll < self.num_of_players as f64 {
+                println!("The bankroll is gone");
+                self.display_session();
+                return;
+            }
+            self.play();
+            self.quiz_count(QuizPoint::EndOfRound);
+
+            loop {
+                match get_user_command() {
+                    UserCommand::NextHand => break,
+                    UserCommand::Chart    => print!("{}", render::render(&self.chart, ChartFormat::Terminal, Some(&self.mistakes))),
+                    UserCommand::Count    => self.shoe.display_count(),
+                    UserCommand::Stats    => self.stats.display(),
+                    UserCommand::Quit     => {
+                        self.display_session();
+                        return;
+                    },
+                    UserCommand::Unknown  => println!("UserCommand::Unknown"),
+                }
+            }
         }
     }
 
     pub fn play(&mut self) {
 
-        // TODO: Add betting mechanism
+        // Hands split last round are folded back into one hand per player.
+        self.player_hands.truncate(self.num_of_players as usize);
 
+        // Shuffle before the bets go out, so that they are made on the new shoe.
+        if self.shoe.will_reset_before_deal(self.player_hands.len()) {
+            self.quiz_count(QuizPoint::Shuffle);
+            println!("Shuffling");
+            self.shoe.reset();
+        }
+        else {
+            self.quiz_count(QuizPoint::BeforeBet);
+        }
+        if let Some(wong_rule) = self.wong_rule {
+            self.decide_wong(wong_rule);
+        }
+        self.place_bets();
         self.shoe.deal(&mut self.player_hands, &mut self.dealer_hand);
 
         display_break();
         display_dealer();
         self.dealer_hand.display_hidden_hand();
         display_player();
-        self.player_hands[0].display_hand();
+        self.display_player_hands();
 
+        let mut winnings: f64 = 0.0;
+        let insurance_bet: f64 = if self.seated && self.dealer_hand.cards[1] == 'A' && self.offer_insurance() { self.bets.iter().sum::<f64>() / 2.0 } else { 0.0 };
 
-        let player_outcome: PlayerOutcome = self.players_turn();
+        // The dealer peeks under an ace or ten, and a blackjack ends the round at once.
+        if self.dealer_hand.is_natural() {
+            self.shoe.reveal_hole_card();
+            display_dealer();
+            self.dealer_hand.display_hand();
+            println!("Dealer has blackjack");
+            if insurance_bet > 0.0 {
+                println!("Insurance pays 2 to 1");
+                winnings += 2.0 * insurance_bet;
+            }
+            for hand_index in 0..self.player_hands.len() {
+                let result: HandResult = if self.player_hands[hand_index].is_natural() { HandResult::Push } else { HandResult::Lose };
+                winnings += self.settle_hand(hand_index, result);
+            }
+            self.collect_winnings(winnings);
+            return;
+        }
+        if insurance_bet > 0.0 {
+            println!("Dealer doesn't have blackjack, insurance is lost");
+            winnings -= insurance_bet;
+        }
 
-        let dealer_outcome: PlayerOutcome = self.dealer_turn();
+        let player_outcomes: Vec<PlayerOutcome> = self.players_turn();
+        self.shoe.reveal_hole_card();
+
+        // The dealer only plays out the hand if a player is still standing.
+        let dealer_outcome: PlayerOutcome = if player_outcomes.contains(&PlayerOutcome::Stand) { self.dealer_turn() } else { PlayerOutcome::Stand };
+
+        for (hand_index, player_outcome) in player_outcomes.iter().enumerate() {
+            let player_total: u8 = self.player_hands[hand_index].total();
+            let dealer_total: u8 = self.dealer_hand.total();
+            let result: HandResult = match (player_outcome, &dealer_outcome) {
+                (PlayerOutcome::BlackJack, _)                   => HandResult::BlackJack,
+                (PlayerOutcome::Surrender, _)                   => HandResult::Surrender,
+                (PlayerOutcome::Bust, _)                        => HandResult::Lose,
+                (_, PlayerOutcome::Bust)                        => HandResult::Win,
+                _ if player_total > dealer_total                => HandResult::Win,
+                _ if player_total == dealer_total               => HandResult::Push,
+                _=> HandResult::Lose,
+            };
+            if *player_outcome == PlayerOutcome::Stand && dealer_outcome == PlayerOutcome::Stand {
+                println!("dealer_total = {}, player_total = {}", dealer_total, player_total);
+            }
+            winnings += self.settle_hand(hand_index, result);
+        }
+        self.collect_winnings(winnings);
+    }
 
-        if player_outcome == PlayerOutcome::Stand && dealer_outcome == PlayerOutcome::Stand{
-            
-            if cmp::max(self.dealer_hand.count_a, self.dealer_hand.count_b) >= cmp::max(self.player_hands[0].count_a, self.player_hands[0].count_b) {
-                display_lose();
+    /// Asks whether to play the next round and grades it against `wong_rule`.
+    fn decide_wong(&mut self, wong_rule : WongRule) {
+        let true_count: f64 = self.shoe.betting_true_count();
+        let should_play: bool = wong_rule.should_play(self.seated, true_count);
+
+        let play: bool = get_user_confirmation(if self.seated { "Stay in for this round? (y/n): " } else { "Wong in for this round? (y/n): " });
+        self.wong_score.record(play == should_play);
+        println!("{} {} at true count {:+.1}, {}",
+                 if play == should_play { "CORRECT!" } else { "WRONG!" },
+                 if should_play { "Play" } else { "Watch" },
+                 true_count,
+                 wong_rule.describe());
+        self.seated = play;
+    }
+
+    /// Takes a bet for each seat, grading it against the bet ramp if there is one.
+    fn place_bets(&mut self) {
+        self.bets.clear();
+        if !self.seated {
+            self.bets.resize(self.num_of_players as usize, 0.0);
+            return;
+        }
+
+        let true_count: f64 = self.shoe.betting_true_count();
+        if self.kelly {
+            let advantage: f64 = betting::estimated_advantage(true_count);
+            println!("Kelly bet: {:.1} units (estimated advantage {:+.2}%)", betting::kelly_bet(advantage, self.bankroll), advantage * 100.0);
+        }
+
+        for seat in 0..self.num_of_players {
+            if self.num_of_players > 1 {
+                println!("Seat {}:", seat + 1);
             }
-            else {
-                display_win();
+            // Every seat still to bet keeps at least a unit of the bankroll.
+            let later_seats: f64 = (self.num_of_players - seat - 1) as f64;
+            let bet: f64 = get_user_bet(self.bankroll - self.bets.iter().sum::<f64>() - later_seats);
+
+            if let Some(bet_ramp) = &self.bet_ramp {
+                let units: f64 = bet_ramp.units(true_count) as f64;
+                self.bet_score.record(bet == units);
+                if bet == units {
+                    println!("CORRECT! The ramp bets {} units at true count {:+.1}", units, true_count);
+                }
+                else {
+                    println!("WRONG! The ramp bets {} units at true count {:+.1}", units, true_count);
+                    println!("{}", bet_ramp.describe());
+                }
             }
+            self.bets.push(bet);
+        }
+    }
 
-            println!("dealer_max({},{}) = {}, player_max({}, {}) = {}", self.dealer_hand.count_a,
-                                                                        self.dealer_hand.count_b,
-                                                                        cmp::max(self.dealer_hand.count_a, self.dealer_hand.count_b),
-                                                                        self.player_hands[0].count_a,
-                                                                        self.player_hands[0].count_b,
-                                                                        cmp::max(self.player_hands[0].count_a, self.player_hands[0].count_b));
-            
+    /// Shows how a hand finished and returns what it won or lost.
+    fn settle_hand(&mut self, hand_index : usize, result : HandResult) -> f64 {
+        if self.player_hands.len() > 1 {
+            println!("Hand {}:", hand_index + 1);
+        }
+        match result {
+            HandResult::Win       => display_win(),
+            HandResult::BlackJack => { println!("Blackjack pays 3 to 2"); display_win(); },
+            HandResult::Lose      => display_lose(),
+            HandResult::Push      => println!("Push, the bet is returned"),
+            HandResult::Surrender => println!("Surrendered, half of the bet is returned"),
+        }
+        return result.payout(self.bets[hand_index]);
+    }
+
+    fn collect_winnings(&mut self, winnings : f64) {
+        if !self.seated {
+            println!("Watched this round");
+            return;
+        }
+        self.bankroll += winnings;
+        self.stats.record_round(self.player_hands.len() as u32, winnings);
+        if let Some(profile) = self.profile.as_mut() {
+            profile.bankroll = Some(self.bankroll);
+            save_profile(profile);
+        }
+        println!("{} {} units this round, bankroll {} units", if winnings < 0.0 { "Lost" } else { "Won" }, winnings.abs(), self.bankroll);
+    }
+
+    fn display_session(&self) {
+        self.stats.display();
+        if let Some(count_quiz) = &self.count_quiz {
+            count_quiz.display();
+        }
+        if self.wong_score.asked > 0 {
+            println!("Wonging decisions: {}/{} ({:.0}%)", self.wong_score.correct, self.wong_score.asked, self.wong_score.accuracy() * 100.0);
+        }
+        if self.insurance_score.asked > 0 {
+            println!("Insurance decisions: {}/{} ({:.0}%)", self.insurance_score.correct, self.insurance_score.asked, self.insurance_score.accuracy() * 100.0);
+        }
+        if self.bet_score.asked > 0 {
+            println!("Bets on the ramp: {}/{} ({:.0}%)", self.bet_score.correct, self.bet_score.asked, self.bet_score.accuracy() * 100.0);
+        }
+        if let Some(score) = self.distractions.as_ref().map(|distractions| distractions.score()).filter(|score| score.asked > 0) {
+            println!("Table talk answered: {}/{} ({:.0}%)", score.correct, score.asked, score.accuracy() * 100.0);
+        }
+        println!("Bankroll: {} units", self.bankroll);
+    }
+
+    /// Grades the insurance decision against its exact EV from the unseen
+    /// cards, and shows what the count says: the index table's insurance
+    /// index, or the standard Hi-Lo one without a table.
+    fn offer_insurance(&mut self) -> bool {
+        let true_count: f64 = self.shoe.insurance_true_count();
+        let insurance_index: Option<Condition> = match self.index_table.as_ref() {
+            Some(index_table) => index_table.insurance(),
+            None => Some(HI_LO_INSURANCE),
+        };
+        let count_takes_insurance: bool = insurance_index.is_some_and(|condition| condition.matches(true_count));
+
+        let unseen_cards: Composition = unseen_cards(&self.shoe, &self.dealer_hand);
+        let insurance_ev: f64 = ev::insurance_ev(&unseen_cards);
+        let take_insurance: bool = insurance_ev > 0.0;
+
+        let insured: bool = get_user_confirmation("Insurance? (y/n): ");
+        self.insurance_score.record(insured == take_insurance);
+        println!("{} {} insurance, it is worth {:+.4} units per unit insured with {} tens in {} unseen cards",
+                 if insured == take_insurance { "CORRECT!" } else { "WRONG!" },
+                 if take_insurance { "Take" } else { "Decline" },
+                 insurance_ev,
+                 unseen_cards.count(TEN_RANK),
+                 unseen_cards.total());
+
+        match insurance_index {
+            Some(condition) => println!("The count says {}: insurance is taken at {}, the true count is {:+.1}",
+                                        if count_takes_insurance { "take" } else { "decline" },
+                                        condition.describe(),
+                                        true_count),
+            None => println!("The count says decline: the index table never takes insurance"),
+        }
+        return insured;
+    }
+
+    /// Asks for the count if the quiz is on and picks this moment.
+    fn quiz_count(&mut self, point : QuizPoint) {
+        let count_quiz: &mut CountQuiz = match self.count_quiz.as_mut() {
+            Some(count_quiz) => count_quiz,
+            None => return,
+        };
+        if !count_quiz.should_ask(point) {
+            return;
+        }
+
+        match point {
+            QuizPoint::EndOfRound => println!("End of the round."),
+            QuizPoint::BeforeBet  => println!("Before you bet."),
+            QuizPoint::Shuffle    => println!("The cut card is out, last count of the shoe."),
+        }
+
+        // An answer that isn't a number never matches, so it is marked wrong.
+        let running_count: f64 = self.shoe.running_count();
+        let answer: Option<f64> = get_user_number("What is the running count? ");
+        if count_quiz.grade_running_count(answer.unwrap_or(f64::NAN), running_count) {
+            println!("CORRECT! Running count {:+}", running_count);
         }
-        else if player_outcome == PlayerOutcome::Stand && dealer_outcome == PlayerOutcome::Bust {
-            display_win();
+        else {
+            println!("WRONG! Running count {:+}", running_count);
         }
-        else if player_outcome == PlayerOutcome::Bust && dealer_outcome == PlayerOutcome::Stand {
-            display_lose();
+
+        // Unbalanced systems are played off the running count alone.
+        if self.shoe.counting_system().is_balanced() {
+            let true_count: f64 = self.shoe.true_count();
+            let answer: Option<f64> = get_user_number("What is the true count? ");
+            if count_quiz.grade_true_count(answer.unwrap_or(f64::NAN), true_count) {
+                println!("CORRECT! True count {:+.1}", true_count);
+            }
+            else {
+                println!("WRONG! True count {:+.1}", true_count);
+            }
+        }
+
+        if self.shoe.counting_system().has_ace_side_count() {
+            let aces_remaining: u16 = self.shoe.aces_remaining();
+            let answer: Option<f64> = get_user_number("How many aces remain? ");
+            if count_quiz.grade_aces(answer.unwrap_or(f64::NAN), aces_remaining) {
+                println!("CORRECT! {} aces remain", aces_remaining);
+            }
+            else {
+                println!("WRONG! {} aces remain", aces_remaining);
+            }
         }
-        else if player_outcome == PlayerOutcome::Bust && dealer_outcome == PlayerOutcome::Bust {
-            display_lose();
+    }
+
+    /// Sometimes interrupts with table talk, grading the answer if it has one.
+    fn distract(&mut self) {
+        let distractions: &mut Distractions = match self.distractions.as_mut() {
+            Some(distractions) => distractions,
+            None => return,
+        };
+        if !self.seated || !distractions.should_interrupt() {
+            return;
         }
 
+        let distraction: Distraction = Distraction::random();
+        let answer: Option<f64> = get_user_number(&format!("{} ", distraction.question(&self.bets)));
+        match distractions.grade(distraction, answer, &self.bets, self.shoe.num_of_decks()) {
+            Some(true)  => println!("CORRECT!"),
+            Some(false) => println!("WRONG! The answer is {}", distraction.answer(&self.bets, self.shoe.num_of_decks()).unwrap_or_default()),
+            None => (),
+        }
     }
 
-    fn players_turn(&mut self) -> PlayerOutcome {
+    fn players_turn(&mut self) -> Vec<PlayerOutcome> {
+        let mut outcomes: Vec<PlayerOutcome> = Vec::new();
 
-        let player_hand: &mut Hand = &mut self.player_hands[0];
+        // Splitting adds hands, so the number of hands to play can grow as we go.
+        let mut hand_index: usize = 0;
+        while hand_index < self.player_hands.len() {
+            self.distract();
+            if self.seated && self.player_hands.len() > 1 {
+                println!("Hand {}:", hand_index + 1);
+            }
+            outcomes.push(self.play_hand(hand_index));
+            hand_index += 1;
+        }
+
+        return outcomes;
+    }
+
+    fn play_hand(&mut self, hand_index : usize) -> PlayerOutcome {
 
         let mut outcome: PlayerOutcome = PlayerOutcome::InProgress;
-        
+
         while outcome == PlayerOutcome::InProgress {
 
-            let action: UserAction = get_user_action();
+            let player_hand: &mut Hand = &mut self.player_hands[hand_index];
+            let split_hand: bool = player_hand.split;
 
-            let basic_strat_action: UserAction = hard_total_basic_strategy(player_hand, &mut self.dealer_hand);
-            if action != basic_strat_action {
-                println!("WRONG! Correct Choice {:?}", basic_strat_action);
+            if !split_hand && player_hand.is_natural() {
+                return PlayerOutcome::BlackJack;
             }
-            else {
-                println!("CORRECT! Choice {:?}", basic_strat_action);
+
+            // Split aces only receive one card each.
+            if split_hand && player_hand.cards[0] == 'A' {
+                return PlayerOutcome::Stand;
+            }
+
+            let first_two_cards: bool = player_hand.cards.len() == 2;
+            let can_double: bool = first_two_cards && (!split_hand || self.rules.double_after_split);
+            let can_split: bool = player_hand.is_pair() && !split_hand;
+            let can_surrender: bool = first_two_cards && !split_hand && self.rules.late_surrender;
+            let actions: Vec<UserAction> = strategy::available_actions(can_double, can_split, can_surrender);
+
+            // While wonged out the seat is played by the chart, like anyone else at the table.
+            let upcard: char = self.dealer_hand.cards[1];
+            let (action, response_time): (UserAction, Option<Duration>) = match self.time_limit {
+                _ if !self.seated => (self.chart.lookup(&player_hand.cards, upcard, can_split).resolve(can_double, can_surrender), None),
+                Some(time_limit) => {
+                    let (action, response_time) = get_timed_user_action(time_limit);
+                    (action, Some(response_time))
+                },
+                None => (get_user_action(), None),
+            };
+            // With input closed nobody is left to play the hand out.
+            if action == UserAction::Unknown && input_closed() {
+                return PlayerOutcome::Stand;
+            }
+            if action != UserAction::Unknown && !actions.contains(&action) {
+                println!("{:?} is not allowed on this hand", action);
+                continue;
+            }
+
+            if self.seated {
+                let basic_strat_action: UserAction = self.chart.lookup(&player_hand.cards, upcard, can_split).resolve(can_double, can_surrender);
+                let true_count: f64 = self.shoe.true_count();
+                let deviation: Option<&Deviation> = self.index_table.as_ref().and_then(|index_table| index_table.lookup(&player_hand.cards, upcard, can_split, true_count));
+                let index_action: UserAction = deviation.map_or(basic_strat_action, |deviation| deviation.action.resolve(can_double, can_surrender));
+                let cd_strat_action: UserAction = strategy::composition_dependent_strategy(&player_hand.cards,
+                                                                                            split_hand,
+                                                                                            upcard,
+                                                                                            self.shoe.num_of_decks(),
+                                                                                            &actions,
+                                                                                            &self.rules,
+                                                                                            &mut self.dealer_odds);
+                let correct_action: UserAction = match self.strategy_mode {
+                    StrategyMode::TotalDependent       => index_action,
+                    StrategyMode::CompositionDependent => cd_strat_action,
+                };
+                let timed_out: bool = response_time.zip(self.time_limit).is_some_and(|(response_time, time_limit)| response_time > time_limit);
+                let correct: bool = action == correct_action && !timed_out;
+                if !correct {
+                    if action == correct_action {
+                        println!("WRONG! Too slow, {:?} took {:.1}s", action, response_time.unwrap_or_default().as_secs_f64());
+                    }
+                    else {
+                        println!("WRONG! Correct Choice {:?}", correct_action);
+                    }
+                    if let Some((section, row)) = chart::cell(&player_hand.cards, can_split) {
+                        *self.mistakes.entry((section, row, chart::upcard_column(upcard))).or_insert(0) += 1;
+                    }
+                }
+                else {
+                    println!("CORRECT! Choice {:?}", correct_action);
+                }
+                if let Some((section, row)) = chart::cell(&player_hand.cards, can_split) {
+                    if let Some(profile) = self.profile.as_mut() {
+                        profile.record_decision(review::today(), section, row, chart::upcard_column(upcard), correct);
+                    }
+                    self.review_schedule.record(section, row, chart::upcard_column(upcard), &self.rules.code(), correct, review::today());
+                    if let Some(path) = &self.review_file {
+                        if let Err(error) = self.review_schedule.save(path) {
+                            println!("could not save review schedule: {}", error);
+                        }
+                    }
+                }
+                if correct_action == basic_strat_action {
+                    println!("{}", explain::rule_of_thumb(&player_hand.cards, upcard, can_split, basic_strat_action));
+                }
+                if let Some(deviation) = deviation.filter(|_deviation| index_action != basic_strat_action) {
+                    println!("The count changes the play, {} (true count {:+.1}); basic strategy plays {:?}", deviation.describe(), true_count, basic_strat_action);
+                }
+                // A wrong play can be an index play made at the wrong count.
+                if let Some(index_table) = self.index_table.as_ref().filter(|_index_table| action != correct_action) {
+                    for deviation in index_table.deviations_for(&player_hand.cards, upcard, can_split) {
+                        if deviation.action.resolve(can_double, can_surrender) == action {
+                            println!("Not at this count, {}, and the true count is {:+.1}", deviation.describe(), true_count);
+                        }
+                    }
+                }
+                if cd_strat_action != basic_strat_action {
+                    println!("Composition-dependent strategy plays {:?} here, total-dependent basic strategy plays {:?}", cd_strat_action, basic_strat_action);
+                }
+
+                // Cached dealer odds only stay useful until the shoe is reshuffled.
+                if self.shoe.num_of_shuffles() != self.shoe_shuffles {
+                    self.shoe_shuffles = self.shoe.num_of_shuffles();
+                    self.dealer_odds.clear();
+                }
+
+                let unseen_cards: Composition = unseen_cards(&self.shoe, &self.dealer_hand);
+                let mut action_evs: ActionEvs = ev::evaluate(&player_hand.cards, split_hand, upcard, &unseen_cards, &self.rules, &mut self.dealer_odds);
+                action_evs.restrict(&actions);
+                action_evs.display();
+                let (best_action, _best_ev) = action_evs.best();
+                let mistake_cost: f64 = action_evs.ev(correct_action).zip(action_evs.ev(action)).map_or(0.0, |(correct_ev, ev)| correct_ev - ev);
+                if action != correct_action && mistake_cost > 0.0 {
+                    println!("{:?} gives up {:.4} units of EV against {:?}", action, mistake_cost, correct_action);
+                }
+                else if action != correct_action && mistake_cost < 0.0 {
+                    println!("With these exact cards {:?} is worth {:.4} units more than {:?}, but the strategy plays {:?}", action, -mistake_cost, correct_action, correct_action);
+                }
+                else if let Some(cost) = action_evs.cost(action).filter(|cost| *cost > 0.0) {
+                    println!("{:?} costs {:.4} units of EV compared to {:?}", action, cost, best_action);
+                }
+
+                let category: DecisionCategory = DecisionCategory::of(chart::cell(&player_hand.cards, can_split).map(|(section, _row)| section),
+                                                                      index_action != basic_strat_action,
+                                                                      action,
+                                                                      correct_action);
+                let cards: Vec<String> = player_hand.cards.iter().map(|card| card.to_string()).collect();
+                self.stats.record_decision(category, Decision {
+                    situation : format!("{} vs {}", cards.join(" "), upcard),
+                    action,
+                    correct_action,
+                    cost : mistake_cost,
+                    seconds : response_time.map(|response_time| response_time.as_secs_f64()),
+                    timed_out,
+                });
             }
 
             match action {
                 UserAction::Hit     => outcome = if self.shoe.hit(player_hand) { PlayerOutcome::InProgress } else { PlayerOutcome::Bust },
                 UserAction::Stand   => outcome = PlayerOutcome::Stand,
                 UserAction::Unknown => println!("UserAction::Unknown"),
-                UserAction::DoubleDown => { self.shoe.hit(&mut self.dealer_hand); outcome = PlayerOutcome::Stand },
+                UserAction::DoubleDown => {
+                    self.bets[hand_index] *= 2.0;
+                    outcome = if self.shoe.hit(player_hand) { PlayerOutcome::Stand } else { PlayerOutcome::Bust };
+                },
+                UserAction::Split     => {
+                    let mut second_hand: Hand = player_hand.split();
+                    self.shoe.hit(player_hand);
+                    self.shoe.hit(&mut second_hand);
+                    self.player_hands.insert(hand_index + 1, second_hand);
+                    self.bets.insert(hand_index + 1, self.bets[hand_index]);
+                },
+                UserAction::Surrender => outcome = PlayerOutcome::Surrender,
             }
 
             display_break();
             display_dealer();
             self.dealer_hand.display_hidden_hand();
             display_player();
-            player_hand.display_hand();
-            if player_hand.is_busted() {
+            self.display_player_hands();
+            if self.player_hands[hand_index].is_busted() {
                 display_bust();
             }
         }
@@ -125,14 +683,15 @@ impl Game {
 
     fn dealer_turn(&mut self) -> PlayerOutcome {
 
-        let player_hand: &mut Hand = &mut self.player_hands[0];
         let mut outcome: PlayerOutcome = PlayerOutcome::InProgress;
         
         while outcome == PlayerOutcome::InProgress {
 
             let mut action: UserAction = UserAction::Hit;
 
-            if self.dealer_hand.count_a >= 17 || self.dealer_hand.count_b >= 17 {
+            let dealer_total: u8 = self.dealer_hand.total();
+            let hits_soft_17: bool = self.dealer_hand.is_soft() && self.rules.dealer_hits_soft_17;
+            if dealer_total > 17 || (dealer_total == 17 && !hits_soft_17) {
                 action = UserAction::Stand;
             }
             
@@ -141,6 +700,8 @@ impl Game {
                 UserAction::Stand   => outcome = PlayerOutcome::Stand,
                 UserAction::Unknown => println!("UserAction::Unknown"),
                 UserAction::DoubleDown => println!("UserAction::DoubleDown"),
+                UserAction::Split     => println!("UserAction::Split"),
+                UserAction::Surrender => println!("UserAction::Surrender"),
             }
 
             display_break();
@@ -150,10 +711,7 @@ impl Game {
                 display_bust();
             }
             display_player();
-            player_hand.display_hand();
-            if player_hand.is_busted() {
-                display_bust();
-            }
+            self.display_player_hands();
 
             thread::sleep(Duration::from_secs(1));
         }
@@ -161,6 +719,15 @@ impl Game {
         return outcome;
     }
 
+    fn display_player_hands(&mut self) {
+        for player_hand in self.player_hands.iter_mut() {
+            player_hand.display_hand();
+            if player_hand.is_busted() {
+                display_bust();
+            }
+        }
+    }
+
 }
 
 
@@ -168,47 +735,91 @@ impl Game {
 enum PlayerOutcome {
     Bust,
     Stand,
-    // BlackJack,
+    Surrender,
+    BlackJack,
     InProgress,
 }
 
+/// How a hand finished once the dealer has played.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HandResult {
+    Win,
+    BlackJack,
+    Lose,
+    Push,
+    Surrender,
+}
+
+impl HandResult {
+    /// What a hand with `bet` on it wins, or loses when negative.
+    fn payout(&self, bet : f64) -> f64 {
+        match self {
+            HandResult::Win       => return bet,
+            HandResult::BlackJack => return 1.5 * bet,
+            HandResult::Lose      => return -bet,
+            HandResult::Push      => return 0.0,
+            HandResult::Surrender => return -0.5 * bet,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
-enum UserAction {
-    Hit,
-    Stand,
-    DoubleDown,
+enum UserCommand {
+    NextHand,
+    Chart,
+    Count,
+    Stats,
+    Quit,
     Unknown,
 }
 
-fn user_input_to_action(input: &str) -> UserAction {
-    // let lowercase_input: String = input.to_lowercase();
+fn user_input_to_command(input: &str) -> UserCommand {
     match input {
-        "h" => return UserAction::Hit,
-        "s" => return UserAction::Stand,
-        "d" => return UserAction::DoubleDown,
-        _=>    return UserAction::Unknown,
+        "n" | "" => return UserCommand::NextHand,
+        "c" => return UserCommand::Chart,
+        "o" => return UserCommand::Count,
+        "s" => return UserCommand::Stats,
+        "q" => return UserCommand::Quit,
+        _=>    return UserCommand::Unknown,
     }
 }
 
-fn get_user_action() -> UserAction {
-
-    use std::io::{stdin,stdout,Write};
-    let mut s=String::new();
-    print!("(H)it, (S)tand, or (D)ouble Down: ");
-    let _=stdout().flush();
-    stdin().read_line(&mut s).expect("Did not enter a correct string");
-    if let Some('\n')=s.chars().next_back() {
-        s.pop();
+fn get_user_command() -> UserCommand {
+    print!("(N)ext hand, (C)hart, C(o)unt, (S)tats, or (Q)uit: ");
+    let input: String = read_user_input();
+    if input_closed() {
+        return UserCommand::Quit;
     }
-    if let Some('\r')=s.chars().next_back() {
-        s.pop();
+    return user_input_to_command(&input);
+}
+
+/// Reads a bet of up to `max_bet` units, which must be at least one, taking
+/// one unit if nothing is entered or input has been closed.
+fn get_user_bet(max_bet : f64) -> f64 {
+    loop {
+        print!("Bet in units [1]: ");
+        let input: String = read_user_input();
+        if input.trim().is_empty() || input_closed() {
+            return 1.0;
+        }
+        match input.trim().parse::<f64>() {
+            Ok(bet) if bet > 0.0 && bet <= max_bet => return bet,
+            Ok(bet) if bet > max_bet => println!("At most {} units can go on this seat", max_bet),
+            _=> println!("A bet is a number of units"),
+        }
     }
-    println!("You typed: {}",s);
-    return user_input_to_action(&s);
 }
 
 
 
+
+/// The cards the player hasn't seen: those left in the shoe and the dealer's hole card.
+fn unseen_cards(shoe : &Shoe, dealer_hand : &Hand) -> Composition {
+    let mut unseen_cards: Composition = shoe.composition();
+    unseen_cards.add(rank_index(dealer_hand.cards[0]));
+    return unseen_cards;
+}
+
 fn display_bust() {
     let busted_str: &str = " ___ _   _ ___ _____ _ \n\
                             | _ ) | | / __|_   _| |\n\
@@ -261,86 +872,343 @@ fn display_break() {
 }
 
 
-fn hard_total_basic_strategy(player_hand : &mut Hand, dealer_hand : &mut Hand) -> UserAction {
-    // A hard total is any hand that does not start with an ace in it, 
-    // or it has been dealt an ace that can only be counted as 1 instead of 11.
-    
-    let dealer_upcard: char = dealer_hand.cards[1];
-    let player_total: u8 = player_hand.count_a;
-
-    // 17 and up always stands
-    if player_total >= 17 { return UserAction::Stand; }
-    
-    // 16 stands against dealer 2 through 6, otherwise hit.
-    if player_total == 16 && dealer_upcard == '2' { return UserAction::Stand; }
-    if player_total == 16 && dealer_upcard == '3' { return UserAction::Stand; }
-    if player_total == 16 && dealer_upcard == '4' { return UserAction::Stand; }
-    if player_total == 16 && dealer_upcard == '5' { return UserAction::Stand; }
-    if player_total == 16 && dealer_upcard == '6' { return UserAction::Stand; }
-    if player_total == 16  { return UserAction::Hit; }
-
-    // 15 stands against dealer 2 through 6, otherwise hit.
-    if player_total == 15 && dealer_upcard == '2' { return UserAction::Stand; }
-    if player_total == 15 && dealer_upcard == '3' { return UserAction::Stand; }
-    if player_total == 15 && dealer_upcard == '4' { return UserAction::Stand; }
-    if player_total == 15 && dealer_upcard == '5' { return UserAction::Stand; }
-    if player_total == 15 && dealer_upcard == '6' { return UserAction::Stand; }
-    if player_total == 15 { return UserAction::Hit; }
-
-    // 14 stands against dealer 2 through 6, otherwise hit.
-    if player_total == 14 && dealer_upcard == '2' { return UserAction::Stand; }
-    if player_total == 14 && dealer_upcard == '3' { return UserAction::Stand; }
-    if player_total == 14 && dealer_upcard == '4' { return UserAction::Stand; }
-    if player_total == 14 && dealer_upcard == '5' { return UserAction::Stand; }
-    if player_total == 14 && dealer_upcard == '6' { return UserAction::Stand; }
-    if player_total == 14  { return UserAction::Hit; }
-
-    // 13 stands against dealer 2 through 6, otherwise hit.
-    if player_total == 13 && dealer_upcard == '2' { return UserAction::Stand; }
-    if player_total == 13 && dealer_upcard == '3' { return UserAction::Stand; }
-    if player_total == 13 && dealer_upcard == '4' { return UserAction::Stand; }
-    if player_total == 13 && dealer_upcard == '5' { return UserAction::Stand; }
-    if player_total == 13 && dealer_upcard == '6' { return UserAction::Stand; }
-    if player_total == 13  { return UserAction::Hit; }
-
-    // 12 stands against dealer 4 through 6, otherwise hit.
-    if player_total == 12 && dealer_upcard == '4' { return UserAction::Stand; }
-    if player_total == 12 && dealer_upcard == '5' { return UserAction::Stand; }
-    if player_total == 12 && dealer_upcard == '6' { return UserAction::Stand; }
-    if player_total == 12 { return UserAction::Hit; }
-
-    // 11 always doubles.
-    if player_total == 11 { return UserAction::DoubleDown; }
-    
-    // 10 doubles against dealer 2 through 9 otherwise hit.
-    if player_total == 10 && dealer_upcard == 'T' { return UserAction::Hit; }
-    if player_total == 10 && dealer_upcard == 'J' { return UserAction::Hit; }
-    if player_total == 10 && dealer_upcard == 'Q' { return UserAction::Hit; }
-    if player_total == 10 && dealer_upcard == 'K' { return UserAction::Hit; }
-    if player_total == 10 && dealer_upcard == 'A' { return UserAction::Hit; }
-    if player_total == 10 { return UserAction::DoubleDown; }
-
-    // 9 doubles against dealer 3 through 6 otherwise hit.
-    if player_total == 9 && dealer_upcard == '3' { return UserAction::DoubleDown; }
-    if player_total == 9 && dealer_upcard == '4' { return UserAction::DoubleDown; }
-    if player_total == 9 && dealer_upcard == '5' { return UserAction::DoubleDown; }
-    if player_total == 9 && dealer_upcard == '6' { return UserAction::DoubleDown; }
-    if player_total == 9 { return UserAction::Hit; }
-
-    // 8 always hits.
-    if player_total == 8  { return UserAction::Hit; }
-
-
-    else { return UserAction::Unknown; }
+/// The value following `option` on the command line, e.g. the file after `--chart`.
+fn option_value<'a>(args : &'a [String], option : &str) -> Option<&'a String> {
+    return args.iter().position(|arg| arg == option).and_then(|index| args.get(index + 1));
 }
-                                                                   
 
-fn main() {
+/// The chart to grade against: one loaded with `--chart`, otherwise the built
+/// in chart, solved afresh when the rules differ from the ones it assumes.
+fn active_chart(args : &[String], rules : &Rules) -> Chart {
+    if let Some(path) = option_value(args, "--chart") {
+        let text: String = fs::read_to_string(path).expect("could not read chart file");
+        return Chart::parse(&text).expect("could not parse chart file");
+    }
+    if *rules != Rules::default() {
+        return solver::solve(None, rules);
+    }
+    return Chart::basic_strategy();
+}
+
+/// The system named by `--count`, either a built in one or a file of tags.
+fn counting_system(args : &[String]) -> CountingSystem {
+    if let Some(name) = option_value(args, "--count") {
+        if let Some(system) = CountingSystem::from_name(name) {
+            return system;
+        }
+        let text: String = fs::read_to_string(name).expect("unknown counting system and could not read it as a file");
+        return CountingSystem::parse(&text).expect("could not parse counting system file");
+    }
+    return CountingSystem::default();
+}
 
-    let mut game: Game = Game::new(1, 1, 50);
-    game.play();
-    
+/// Wonging is on with `--wong`, entering at +2 and leaving below +1, or
+/// when either threshold is given with `--wong-in` or `--wong-out`.
+fn wong_rule(args : &[String]) -> Option<WongRule> {
+    let enter: Option<f64> = option_value(args, "--wong-in").and_then(|enter| enter.parse().ok());
+    let exit: Option<f64> = option_value(args, "--wong-out").and_then(|exit| exit.parse().ok());
+    if enter.is_none() && exit.is_none() && !args.iter().any(|arg| arg == "--wong") {
+        return None;
+    }
+    return Some(WongRule::new(enter.unwrap_or(2.0), exit.unwrap_or(1.0)));
+}
 
+const RULE_FLAGS : [&str; 3] = ["--h17", "--no-das", "--no-surrender"];
+
+/// Table rules given on the command line, starting from the defaults.
+fn parse_rules(args : &[String]) -> Rules {
+    let mut rules: Rules = Rules::default();
+    for arg in args {
+        match arg.as_str() {
+            "--h17"          => rules.dealer_hits_soft_17 = true,
+            "--no-das"       => rules.double_after_split = false,
+            "--no-surrender" => rules.late_surrender = false,
+            _=> (),
+        }
+    }
+    return rules;
 }
 
+/// The profile named by `--profile NAME`, if any.
+fn open_profile(args : &[String]) -> Option<Profile> {
+    return option_value(args, "--profile").map(|name| Profile::open(name).expect("could not open profile"));
+}
 
+fn save_profile(profile : &Profile) {
+    if let Err(error) = profile.save() {
+        println!("could not save profile {}: {}", profile.name(), error);
+    }
+}
+
+/// Adds a drill's answers to the profile's history, if there is a profile.
+fn record_drill(profile : &mut Option<Profile>, drill : &str, score : QuizScore) {
+    if let Some(profile) = profile.as_mut() {
+        profile.record_drill(review::today(), drill, score);
+        save_profile(profile);
+    }
+}
+
+fn main() {
+
+    let args: Vec<String> = env::args().collect();
+
+    // A profile keeps the rules it was last played with unless others are given.
+    let mut profile: Option<Profile> = open_profile(&args);
+    let rules: Rules = match profile.as_ref().and_then(|profile| profile.rules) {
+        Some(rules) if !args.iter().any(|arg| RULE_FLAGS.contains(&arg.as_str())) => rules,
+        _=> parse_rules(&args),
+    };
+    if let Some(profile) = profile.as_mut() {
+        profile.rules = Some(rules);
+        save_profile(profile);
+    }
+
+    if args.len() > 1 && args[1] == "profile" {
+        // e.g. `profile alice` shows Alice's progress week by week.
+        match args.get(2) {
+            Some(name) => Profile::open(name).expect("could not open profile").display(),
+            None => println!("profile needs a name"),
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "dealer" {
+        // e.g. `dealer 6 --h17` prints the dealer outcome table for a fresh six deck shoe.
+        let num_of_decks: u8 = args.get(2).and_then(|decks| decks.parse().ok()).unwrap_or(1);
+        if num_of_decks == 0 {
+            println!("A shoe needs at least one deck");
+            return;
+        }
+        DealerOdds::new().display_table(&Composition::full_shoe(num_of_decks), rules.dealer_hits_soft_17);
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "solve" {
+        // e.g. `solve 2 --h17` prints the optimal chart for two decks, `solve inf` for an infinite deck.
+        let num_of_decks: Option<u8> = args.get(2).and_then(|decks| decks.parse().ok());
+        if num_of_decks == Some(0) {
+            println!("A shoe needs at least one deck");
+            return;
+        }
+        print!("{}", solver::solve(num_of_decks, &rules).to_text());
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "chart" {
+        // e.g. `chart --format html > chart.html` writes a printable chart.
+        let format: ChartFormat = option_value(&args, "--format").map(|name| ChartFormat::from_name(name).expect("unknown chart format"))
+                                                                 .unwrap_or(ChartFormat::Terminal);
+        print!("{}", render::render(&active_chart(&args, &rules), format, None));
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "heatmap" {
+        // e.g. `heatmap --profile alice --format svg > alice.svg` exports Alice's accuracy in each cell.
+        let format: ChartFormat = option_value(&args, "--format").map(|name| ChartFormat::from_name(name).expect("unknown chart format"))
+                                                                 .unwrap_or(ChartFormat::Terminal);
+        match &profile {
+            Some(profile) => print!("{}", render::render_heatmap(&active_chart(&args, &rules), &profile.cell_scores(), format)),
+            None => println!("heatmap needs --profile NAME"),
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "countdown" {
+        // e.g. `countdown --decks 2 --group 3 --delay 500` shows two decks three cards at a time.
+        let mut settings: CountdownSettings = CountdownSettings::default();
+        if let Some(num_of_decks) = option_value(&args, "--decks").and_then(|decks| decks.parse().ok()) {
+            settings.num_of_decks = num_of_decks;
+        }
+        if let Some(group_size) = option_value(&args, "--group").and_then(|group| group.parse().ok()) {
+            settings.group_size = group_size;
+        }
+        if let Some(delay) = option_value(&args, "--delay").and_then(|delay| delay.parse().ok()) {
+            settings.delay = Duration::from_millis(delay);
+        }
+
+        let history_file: String = profile.as_ref().map_or(countdown::HISTORY_FILE.to_string(), |profile| profile.file(countdown::HISTORY_FILE));
+        let result: CountdownResult = match countdown::run(&settings, counting_system(&args)) {
+            Some(result) => result,
+            None => return,
+        };
+        if let Err(error) = countdown::save_result(&history_file, &result) {
+            println!("could not save countdown history: {}", error);
+        }
+        countdown::display_trend(&countdown::load_history(&history_file));
+        record_drill(&mut profile, "countdown", QuizScore { asked : 1, correct : result.correct as u32 });
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "wong" {
+        // e.g. `wong 8 --shoes 5000 --wong-in 3` compares wonging strategies over 5000 eight deck shoes.
+        let num_of_decks: u8 = args.get(2).and_then(|decks| decks.parse().ok()).unwrap_or(6);
+        let num_of_shoes: u32 = option_value(&args, "--shoes").and_then(|shoes| shoes.parse().ok()).unwrap_or(2000);
+        if num_of_decks == 0 {
+            println!("A shoe needs at least one deck");
+            return;
+        }
+        let chart: Chart = active_chart(&args, &rules);
+        let system: CountingSystem = counting_system(&args);
+
+        let mut strategies: Vec<Option<WongRule>> = vec!(None);
+        strategies.extend([(1.0, 0.0), (2.0, 0.0), (2.0, 1.0), (3.0, 1.0), (3.0, 2.0), (4.0, 2.0)].iter().map(|(enter, exit)| Some(WongRule::new(*enter, *exit))));
+        if let Some(wong_rule) = wong_rule(&args) {
+            strategies.push(Some(wong_rule));
+        }
+
+        println!("{} shoes of {} decks, flat one unit bets played by the chart", num_of_shoes, num_of_decks);
+        println!("{:<28} {:>8} {:>10} {:>14} {:>14}", "Strategy", "Played", "Units won", "Per 100 played", "Per 100 seen");
+        for wong_rule in strategies {
+            let result: SimulationResult = wonging::simulate(wong_rule, num_of_shoes, num_of_decks, 75, &system, &chart, &rules);
+            println!("{:<28} {:>7.1}% {:>10.1} {:>14.2} {:>14.2}",
+                     wong_rule.map_or("Play every round".to_string(), |wong_rule| wong_rule.describe()),
+                     result.rounds_played as f64 * 100.0 / result.rounds_seen as f64,
+                     result.units_won,
+                     result.win_per_100_played(),
+                     result.win_per_100_seen());
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "discard" {
+        // e.g. `discard 8 --rounds 10` asks for ten estimates from an eight deck shoe.
+        let num_of_decks: u8 = args.get(2).and_then(|decks| decks.parse().ok()).unwrap_or(6);
+        let rounds: u32 = option_value(&args, "--rounds").and_then(|rounds| rounds.parse().ok()).unwrap_or(5);
+        let score: QuizScore = discard::run(num_of_decks, rounds);
+        record_drill(&mut profile, "discard", score);
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "flash" {
+        // e.g. `flash --filter soft --rounds 50` drills fifty soft hands.
+        let filter: HandFilter = option_value(&args, "--filter").map(|name| HandFilter::from_name(name).expect("unknown hand filter"))
+                                                                .unwrap_or(HandFilter::All);
+        let rounds: u32 = option_value(&args, "--rounds").and_then(|rounds| rounds.parse().ok()).unwrap_or(20);
+        let num_of_decks: u8 = option_value(&args, "--decks").and_then(|decks| decks.parse().ok()).unwrap_or(6);
+        let strategy_mode: StrategyMode = if args.iter().any(|arg| arg == "--cd") { StrategyMode::CompositionDependent } else { StrategyMode::TotalDependent };
+        let review_file: String = profile.as_ref().map_or(review::REVIEW_FILE.to_string(), |profile| profile.file(review::REVIEW_FILE));
+        let mut schedule: ReviewSchedule = ReviewSchedule::load(&review_file);
+        let score: QuizScore = flash::run(filter, rounds, num_of_decks, strategy_mode, &active_chart(&args, &rules), &rules, &mut schedule);
+        if let Err(error) = schedule.save(&review_file) {
+            println!("could not save review schedule: {}", error);
+        }
+        record_drill(&mut profile, "flash", score);
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "aces" {
+        // e.g. `aces 6 --rounds 3 --delay 500` asks for the aces left in three six deck shoes.
+        let num_of_decks: u8 = args.get(2).and_then(|decks| decks.parse().ok()).unwrap_or(2);
+        let rounds: u32 = option_value(&args, "--rounds").and_then(|rounds| rounds.parse().ok()).unwrap_or(5);
+        let delay: u64 = option_value(&args, "--delay").and_then(|delay| delay.parse().ok()).unwrap_or(800);
+        let score: QuizScore = aces::run(num_of_decks, rounds, Duration::from_millis(delay));
+        record_drill(&mut profile, "aces", score);
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "lesson" {
+        // e.g. `lesson --profile alice` takes Alice's next lesson, `lesson 2 --profile alice` retakes the second.
+        let profile: &mut Profile = match profile.as_mut() {
+            Some(profile) => profile,
+            None => {
+                println!("lessons need --profile NAME");
+                return;
+            },
+        };
+        curriculum::display(profile.lessons_passed);
+        let number: usize = args.get(2).and_then(|number| number.parse().ok()).unwrap_or(profile.lessons_passed + 1);
+        let lesson: Lesson = match number.checked_sub(1).and_then(|index| LESSONS.get(index)) {
+            Some(lesson) if number <= profile.lessons_passed + 1 => *lesson,
+            Some(_lesson) => {
+                println!("Lesson {} is locked until lesson {} is passed", number, profile.lessons_passed + 1);
+                return;
+            },
+            None => {
+                println!("There are {} lessons", LESSONS.len());
+                return;
+            },
+        };
+
+        let review_file: String = profile.file(review::REVIEW_FILE);
+        let mut schedule: ReviewSchedule = ReviewSchedule::load(&review_file);
+        let score: QuizScore = lesson.run(&active_chart(&args, &rules), &rules, &mut schedule);
+        if let Err(error) = schedule.save(&review_file) {
+            println!("could not save review schedule: {}", error);
+        }
+
+        profile.record_drill(review::today(), lesson.drill(), score);
+        if lesson.passed(score) {
+            println!("PASSED! {}: {}/{} correct", lesson.name(), score.correct, score.asked);
+            profile.lessons_passed = profile.lessons_passed.max(number);
+            if let Some(next) = LESSONS.get(number) {
+                println!("Unlocked lesson {}: {}", number + 1, next.name());
+            }
+        }
+        else {
+            println!("Not yet. {} needs {:.0}% of {} questions, try again", lesson.name(), lesson.pass_mark() * 100.0, lesson.rounds());
+        }
+        save_profile(profile);
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "totals" {
+        // e.g. `totals --rounds 30` asks for the totals of thirty hands.
+        let rounds: u32 = option_value(&args, "--rounds").and_then(|rounds| rounds.parse().ok()).unwrap_or(10);
+        let score: QuizScore = totals::run(rounds);
+        record_drill(&mut profile, "totals", score);
+        return;
+    }
+
+    // e.g. `casino 6 --seats 3` plays three seats of a six deck shoe with the
+    // count quizzed, bets graded on the ramp and table talk to answer.
+    let casino: bool = args.len() > 1 && args[1] == "casino";
+    let mut game: Game = if casino {
+        Game::new(option_value(&args, "--seats").and_then(|seats| seats.parse().ok()).unwrap_or(2),
+                  args.get(2).and_then(|decks| decks.parse().ok()).unwrap_or(6),
+                  75)
+    }
+    else {
+        Game::new(1, 1, 50)
+    };
+    game.set_rules(rules);
+    game.set_chart(active_chart(&args, &rules));
+    match profile {
+        Some(profile) => game.set_profile(profile),
+        None => game.set_review_file(review::REVIEW_FILE),
+    }
+    game.set_counting_system(counting_system(&args));
+    if let Some(path) = option_value(&args, "--index-table") {
+        let text: String = fs::read_to_string(path).expect("could not read index table file");
+        game.set_index_table(IndexTable::parse(&text).expect("could not parse index table file"));
+    }
+    else if args.iter().any(|arg| arg == "--deviations") {
+        game.set_index_table(IndexTable::illustrious_18_fab_4());
+    }
+    if let Some(bankroll) = option_value(&args, "--bankroll").and_then(|bankroll| bankroll.parse().ok()) {
+        game.set_bankroll(bankroll);
+    }
+    if let Some(path) = option_value(&args, "--bet-ramp-file") {
+        let text: String = fs::read_to_string(path).expect("could not read bet ramp file");
+        game.set_bet_ramp(BetRamp::parse(&text).expect("could not parse bet ramp file"));
+    }
+    else if casino || args.iter().any(|arg| arg == "--bet-ramp") {
+        game.set_bet_ramp(BetRamp::default_ramp());
+    }
+    game.set_kelly(args.iter().any(|arg| arg == "--kelly"));
+    if let Some(wong_rule) = wong_rule(&args) {
+        game.set_wong_rule(wong_rule);
+    }
+    if casino || args.iter().any(|arg| arg == "--quiz") {
+        game.set_count_quiz(CountQuiz::new(0.25));
+    }
+    if casino {
+        game.set_distractions(Distractions::new(0.2));
+    }
+    if args.iter().any(|arg| arg == "--cd") {
+        game.set_strategy_mode(StrategyMode::CompositionDependent);
+    }
+    if let Some(seconds) = option_value(&args, "--time-limit").and_then(|seconds| seconds.parse().ok()) {
+        game.set_time_limit(Duration::from_secs_f64(seconds));
+    }
+    game.run();
+}